@@ -51,7 +51,8 @@ fn handle_window_event(window: &mut glfw::Window, event: glfw::WindowEvent) {
         glfw::WindowEvent::Key(Key::Escape, _, Action::Press, _) => window.set_should_close(true),
         glfw::WindowEvent::Key(Key::Space, _, Action::Press, _) => match window.get_cursor_mode() {
             CursorMode::Disabled => window.set_cursor_mode(CursorMode::Normal),
-            CursorMode::Normal => window.set_cursor_mode(CursorMode::Disabled),
+            CursorMode::Normal => window.set_cursor_mode(CursorMode::Captured),
+            CursorMode::Captured => window.set_cursor_mode(CursorMode::Disabled),
             _ => {}
         },
         _ => {}
@@ -71,7 +71,12 @@ fn main() {
                         glfw.with_primary_monitor(|_: &mut _, m: Option<&mut glfw::Monitor>| {
                             let monitor = m.unwrap();
 
-                            let mode = monitor.get_video_mode().unwrap();
+                            // Prefer the highest refresh rate the current resolution supports.
+                            let current = monitor.get_video_mode().unwrap();
+                            let query = glfw::VideoModeQuery::new()
+                                .exact_resolution(current.width, current.height)
+                                .bit_depth(current.red_bits);
+                            let mode = monitor.best_video_mode(&query).unwrap_or(current);
 
                             window.set_monitor(
                                 glfw::WindowMode::FullScreen(&monitor),
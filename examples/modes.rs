@@ -33,8 +33,18 @@ fn main() {
         for monitor in monitors.iter() {
             println!("{:?}:", monitor.get_name());
             for mode in monitor.get_video_modes().iter() {
-                println!("  {:?}", *mode);
+                let (w, h) = mode.aspect_ratio();
+                println!("  {:?} ({}:{})", *mode, w, h);
             }
+
+            let query = glfw::VideoModeQuery::new()
+                .resolution(1920, 1080)
+                .refresh_rate(144)
+                .bit_depth(8);
+            println!(
+                "  Closest to 1920 x 1080 at 144 Hz with 8-bit color: {:?}",
+                monitor.best_video_mode(&query)
+            );
         }
     });
 }
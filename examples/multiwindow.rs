@@ -21,9 +21,11 @@ use glfw::{Action, Key};
 type WindowInstance = (glfw::PWindow, Receiver<(f64, glfw::WindowEvent)>);
 type WindowVector = Vec<WindowInstance>;
 
-fn add_window(glfw: &mut glfw::Glfw, window_vector: &mut WindowVector) {
-    let (mut window, events) = glfw
-        .create_window(300, 300, "Hello this is window", glfw::WindowMode::Windowed)
+fn add_window(glfw: &mut glfw::Glfw, window_vector: &mut WindowVector, resizable: bool) {
+    // The hints only apply to this window, they don't leak into the next one.
+    let (mut window, events) = glfw::WindowBuilder::new(300, 300, "Hello this is window")
+        .hint(glfw::WindowHint::Resizable(resizable))
+        .build(glfw)
         .expect("Failed to create GLFW window.");
 
     window.set_key_polling(true);
@@ -42,8 +44,8 @@ fn main() {
 
     // Create two windows
     let mut windows = WindowVector::new();
-    add_window(&mut glfw, &mut windows);
-    add_window(&mut glfw, &mut windows);
+    add_window(&mut glfw, &mut windows, false);
+    add_window(&mut glfw, &mut windows, true);
 
     // Loop until we no longer have any open windows
     while !windows.is_empty() {
@@ -13,21 +13,19 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-#[cfg(not(feature = "vulkan"))]
+#[cfg(not(feature = "ash"))]
 fn main() {
-    eprintln!("run with: --features vulkan")
+    eprintln!("run with: --features ash")
 }
 
-#[cfg(feature = "vulkan")]
+#[cfg(feature = "ash")]
 use ash::vk;
 
-#[cfg(feature = "vulkan")]
-use std::ptr;
+#[cfg(feature = "ash")]
+use std::ffi::CStr;
 
-#[cfg(feature = "vulkan")]
+#[cfg(feature = "ash")]
 fn main() {
-    use ash::vk::Handle;
-
     let mut glfw = glfw::init_no_callbacks().unwrap();
 
     glfw.window_hint(glfw::WindowHint::Visible(true));
@@ -39,45 +37,34 @@ fn main() {
 
     assert!(glfw.vulkan_supported());
 
-    let required_extensions = glfw.get_required_instance_extensions().unwrap_or(vec![]);
+    let required_extensions = glfw.required_instance_extensions();
 
     //VK_KHR_surface will always be available if the previous operations were successful
-    assert!(required_extensions.contains(&"VK_KHR_surface".to_string()));
+    assert!(required_extensions.contains(&ash::khr::surface::NAME));
 
     println!("Vulkan required extensions: {:?}", required_extensions);
 
-    //Load up all the entry points using 0 as the VkInstance,
-    //since you can't have an instance before you get vkCreateInstance...
+    //Loading the Vulkan library runs its initialization code, which ash cannot check
     let entry = unsafe { ash::Entry::load().expect("Failed to load Vulkan library.") };
 
-    let instance: ash::Instance = unsafe { create_instance(&entry, &required_extensions) };
-
-    let mut surface: std::mem::MaybeUninit<vk::SurfaceKHR> = std::mem::MaybeUninit::uninit();
+    let instance = create_instance(&entry, &required_extensions);
 
-    if unsafe {
-        window.create_window_surface(
-            instance.handle().as_raw() as _,
-            ptr::null(),
-            surface.as_mut_ptr() as _,
-        )
-    } != vk::Result::SUCCESS.as_raw()
     {
-        panic!("Failed to create GLFW window surface.");
+        let _surface = glfw::Surface::new(&window, &entry, &instance)
+            .expect("Failed to create GLFW window surface.");
+
+        // Use other vulkan stuff here.
     }
 
-    // Use other vulkan stuff here.
+    unsafe { instance.destroy_instance(None) };
 
-    println!("Vulkan instance successfully created. Destruction is automatic with Drop.");
+    println!("Vulkan instance successfully created and destroyed.");
 }
 
-#[cfg(feature = "vulkan")]
-unsafe fn create_instance(entry: &ash::Entry, extensions: &Vec<String>) -> ash::Instance {
-    // Turn the list of extensions into a format that can be passed in InstanceCreateInfo
-    let extensions: Vec<std::ffi::CString> = extensions
-        .iter()
-        .map(|ext| std::ffi::CString::new(ext.clone()).expect("Failed to convert extension name"))
-        .collect();
-    let extension_pointers: Vec<*const i8> = extensions.iter().map(|ext| ext.as_ptr()).collect();
+#[cfg(feature = "ash")]
+fn create_instance(entry: &ash::Entry, extensions: &[&CStr]) -> ash::Instance {
+    let extension_pointers: Vec<*const std::os::raw::c_char> =
+        extensions.iter().map(|ext| ext.as_ptr()).collect();
     //This is literally the bare minimum required to create a blank instance
     //You'll want to fill in this with real data yourself
     let info: vk::InstanceCreateInfo =
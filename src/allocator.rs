@@ -0,0 +1,205 @@
+// Copyright 2013 The GLFW-RS Developers. For a full listing of the authors,
+// refer to the AUTHORS file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Routing of GLFW's internal allocations through a Rust allocator.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::os::raw::c_void;
+use std::ptr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::spin_lock::SpinLock;
+use crate::{ffi, InitError, REF_COUNT_FOR_GLFW};
+
+type DynAllocator = &'static (dyn GlobalAlloc + Sync);
+
+/// The allocator passed to `init_allocator`. GLFW keeps the user pointer, which has to be thin,
+/// so it points at this slot rather than at the allocator itself.
+static ALLOCATOR: SpinLock<Option<DynAllocator>> = SpinLock::new(None);
+
+/// GLFW only passes the block back when freeing it, so every block is preceded by a header
+/// holding its size. The header is as large as the alignment that `malloc` guarantees, so that
+/// the block after it keeps that alignment.
+const HEADER: usize = 16;
+
+fn layout(size: usize) -> Option<Layout> {
+    let size = size.checked_add(HEADER)?;
+    Layout::from_size_align(size, HEADER).ok()
+}
+
+unsafe fn allocator(user: *mut c_void) -> DynAllocator {
+    let slot = &*(user as *const SpinLock<Option<DynAllocator>>);
+    let allocator = *slot.lock();
+    allocator.expect("GLFW allocator called without an allocator set")
+}
+
+unsafe extern "C" fn allocate(size: usize, user: *mut c_void) -> *mut c_void {
+    let layout = match layout(size) {
+        Some(layout) => layout,
+        None => return ptr::null_mut(),
+    };
+    let base = allocator(user).alloc(layout);
+    if base.is_null() {
+        return ptr::null_mut();
+    }
+    (base as *mut usize).write(size);
+    base.add(HEADER) as *mut c_void
+}
+
+unsafe extern "C" fn reallocate(block: *mut c_void, size: usize, user: *mut c_void) -> *mut c_void {
+    if block.is_null() {
+        return allocate(size, user);
+    }
+    let new_layout = match layout(size) {
+        Some(layout) => layout,
+        None => return ptr::null_mut(),
+    };
+    let base = (block as *mut u8).sub(HEADER);
+    let old_size = (base as *const usize).read();
+    let old_layout = layout(old_size).unwrap();
+    let new_base = allocator(user).realloc(base, old_layout, new_layout.size());
+    if new_base.is_null() {
+        return ptr::null_mut();
+    }
+    (new_base as *mut usize).write(size);
+    new_base.add(HEADER) as *mut c_void
+}
+
+unsafe extern "C" fn deallocate(block: *mut c_void, user: *mut c_void) {
+    if block.is_null() {
+        return;
+    }
+    let base = (block as *mut u8).sub(HEADER);
+    let size = (base as *const usize).read();
+    allocator(user).dealloc(base, layout(size).unwrap());
+}
+
+/// Sets the allocator GLFW uses for its internal allocations, or restores the default one when
+/// given `None`.
+///
+/// Like `init_hint`, this takes effect the next time GLFW is initialized, and GLFW keeps using
+/// the same allocator until it is terminated.
+///
+/// Wrapper for `glfwInitAllocator`.
+///
+/// # Returns
+///
+/// `Err(InitError::AlreadyInitialized)` if GLFW is initialized.
+///
+/// # Example
+///
+/// ~~~ignore
+/// static ALLOCATOR: glfw::CountingAllocator = glfw::CountingAllocator::new();
+///
+/// glfw::init_allocator(Some(&ALLOCATOR)).unwrap();
+/// let glfw = glfw::init_no_callbacks().unwrap();
+/// // ...
+/// drop(glfw);
+/// assert_eq!(ALLOCATOR.live_allocations(), 0);
+/// ~~~
+pub fn init_allocator(
+    allocator: Option<&'static (dyn GlobalAlloc + Sync)>,
+) -> Result<(), InitError> {
+    if REF_COUNT_FOR_GLFW.load(Ordering::SeqCst) > 0 {
+        return Err(InitError::AlreadyInitialized);
+    }
+    match allocator {
+        Some(allocator) => {
+            *ALLOCATOR.lock() = Some(allocator);
+            let glfw_allocator = ffi::GLFWallocator {
+                allocate: Some(allocate),
+                reallocate: Some(reallocate),
+                deallocate: Some(deallocate),
+                user: &ALLOCATOR as *const SpinLock<Option<DynAllocator>> as *mut c_void,
+            };
+            unsafe { ffi::glfwInitAllocator(&glfw_allocator) }
+        }
+        None => {
+            unsafe { ffi::glfwInitAllocator(ptr::null()) };
+            *ALLOCATOR.lock() = None;
+        }
+    }
+    Ok(())
+}
+
+/// An allocator that forwards to `std::alloc::System` and counts what is allocated through it.
+///
+/// Intended for use with `init_allocator` to check that GLFW frees everything it allocates.
+#[derive(Debug)]
+pub struct CountingAllocator {
+    allocations: AtomicUsize,
+    deallocations: AtomicUsize,
+    live_bytes: AtomicUsize,
+}
+
+impl CountingAllocator {
+    /// Creates an allocator with all counts at zero.
+    pub const fn new() -> CountingAllocator {
+        CountingAllocator {
+            allocations: AtomicUsize::new(0),
+            deallocations: AtomicUsize::new(0),
+            live_bytes: AtomicUsize::new(0),
+        }
+    }
+
+    /// The number of allocations made so far.
+    pub fn total_allocations(&self) -> usize {
+        self.allocations.load(Ordering::SeqCst)
+    }
+
+    /// The number of allocations that have not been freed.
+    pub fn live_allocations(&self) -> usize {
+        self.total_allocations() - self.deallocations.load(Ordering::SeqCst)
+    }
+
+    /// The number of bytes in allocations that have not been freed.
+    ///
+    /// Blocks allocated for GLFW are counted with the 16 byte header that records their size.
+    pub fn live_bytes(&self) -> usize {
+        self.live_bytes.load(Ordering::SeqCst)
+    }
+}
+
+impl Default for CountingAllocator {
+    fn default() -> CountingAllocator {
+        CountingAllocator::new()
+    }
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            self.allocations.fetch_add(1, Ordering::SeqCst);
+            self.live_bytes.fetch_add(layout.size(), Ordering::SeqCst);
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        self.deallocations.fetch_add(1, Ordering::SeqCst);
+        self.live_bytes.fetch_sub(layout.size(), Ordering::SeqCst);
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_ptr = System.realloc(ptr, layout, new_size);
+        if !new_ptr.is_null() {
+            self.live_bytes.fetch_sub(layout.size(), Ordering::SeqCst);
+            self.live_bytes.fetch_add(new_size, Ordering::SeqCst);
+        }
+        new_ptr
+    }
+}
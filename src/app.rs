@@ -0,0 +1,371 @@
+// Copyright 2013 The GLFW-RS Developers. For a full listing of the authors,
+// refer to the AUTHORS file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Ready-made main loops.
+
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::rc::Rc;
+
+use crate::{
+    ffi, flush_messages, Context, Glfw, GlfwReceiver, JoystickEvent, JoystickId, MonitorEvent,
+    PWindow, Window, WindowBuilder, WindowCreationError, WindowEvent, WindowId,
+};
+
+/// The longest frame time `run_fixed_timestep` catches up on. Anything beyond it is dropped, so
+/// that a slow update cannot cause ever more updates per frame.
+const MAX_FRAME_TIME: f64 = 0.25;
+
+/// The callbacks of `run_fixed_timestep`.
+pub trait FixedTimestepHandler {
+    /// Called for every event received by the window, before the updates of the frame.
+    fn event(&mut self, window: &mut Window, time: f64, event: WindowEvent) {
+        let _ = (window, time, event);
+    }
+
+    /// Advances the simulation by `dt` seconds, which is the same on every call.
+    fn update(&mut self, window: &mut Window, dt: f64);
+
+    /// Draws the current frame.
+    ///
+    /// `alpha` is the fraction of an update step that has passed since the last update, in
+    /// `[0, 1)`, for interpolating between the last two simulation states.
+    fn render(&mut self, window: &mut Window, alpha: f64);
+}
+
+/// Runs a fixed timestep main loop until the window should close.
+///
+/// Each frame polls events and passes them to `handler.event`, then calls `handler.update` as
+/// many times as needed for the simulation to catch up with `Glfw::get_time` at `update_hz`
+/// steps per second, then calls `handler.render` and swaps the buffers. Windows without a client
+/// API, such as Vulkan ones, are not swapped.
+///
+/// At most a quarter of a second is caught up on per frame, so after a stall the simulation
+/// slows down instead of spiralling into ever longer frames.
+///
+/// # Panics
+///
+/// If `update_hz` is not positive and finite.
+///
+/// # Example
+///
+/// ~~~ignore
+/// struct Game { position: f64, previous: f64 }
+///
+/// impl glfw::app::FixedTimestepHandler for Game {
+///     fn update(&mut self, _: &mut glfw::Window, dt: f64) {
+///         self.previous = self.position;
+///         self.position += 10.0 * dt;
+///     }
+///
+///     fn render(&mut self, _: &mut glfw::Window, alpha: f64) {
+///         draw(self.previous + (self.position - self.previous) * alpha);
+///     }
+/// }
+///
+/// glfw::app::run_fixed_timestep(&mut glfw, &mut window, &events, 60.0, &mut game);
+/// ~~~
+pub fn run_fixed_timestep<H>(
+    glfw: &mut Glfw,
+    window: &mut Window,
+    events: &GlfwReceiver<(f64, WindowEvent)>,
+    update_hz: f64,
+    handler: &mut H,
+) where
+    H: FixedTimestepHandler + ?Sized,
+{
+    assert!(
+        update_hz.is_finite() && update_hz > 0.0,
+        "update rate must be positive and finite"
+    );
+    let dt = 1.0 / update_hz;
+    let swap = window.get_client_api() != ffi::GLFW_NO_API;
+
+    let mut previous = glfw.get_time();
+    let mut accumulator = 0.0;
+    while !window.should_close() {
+        glfw.poll_events();
+        for (time, event) in flush_messages(events) {
+            handler.event(window, time, event);
+        }
+
+        let now = glfw.get_time();
+        accumulator += (now - previous).min(MAX_FRAME_TIME);
+        previous = now;
+        while accumulator >= dt {
+            handler.update(window, dt);
+            accumulator -= dt;
+        }
+
+        handler.render(window, accumulator / dt);
+        if swap {
+            window.swap_buffers();
+        }
+    }
+}
+
+/// How `Glfw::run_app` waits for events between iterations.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum ControlFlow {
+    /// Process pending events and continue immediately, for applications that render
+    /// continuously.
+    Poll,
+    /// Sleep until at least one event has been received.
+    Wait,
+    /// Sleep until at least one event has been received or the timeout, in seconds, has passed.
+    WaitTimeout(f64),
+}
+
+impl Default for ControlFlow {
+    fn default() -> ControlFlow {
+        ControlFlow::Wait
+    }
+}
+
+/// The callbacks of `Glfw::run_app`.
+///
+/// Every callback receives the `AppContext`, through which windows are created and looked up
+/// and the loop is stopped.
+pub trait ApplicationHandler {
+    /// Called once when the loop starts. This is where the first windows are created.
+    fn init(&mut self, app: &mut AppContext);
+
+    /// Called for every event received by a window created through the `AppContext`.
+    fn window_event(
+        &mut self,
+        app: &mut AppContext,
+        window_id: WindowId,
+        time: f64,
+        event: WindowEvent,
+    );
+
+    /// Called when a joystick is connected or disconnected.
+    fn joystick_event(
+        &mut self,
+        app: &mut AppContext,
+        joystick_id: JoystickId,
+        event: JoystickEvent,
+    ) {
+        let _ = (app, joystick_id, event);
+    }
+
+    /// Called when a monitor is connected or disconnected.
+    ///
+    /// Monitors are identified by name, as a disconnected monitor has been freed by the time
+    /// this is called. Connected ones can be found with `Glfw::with_connected_monitors`.
+    fn monitor_event(&mut self, app: &mut AppContext, name: Option<String>, event: MonitorEvent) {
+        let _ = (app, name, event);
+    }
+
+    /// Called when all pending events have been handled, before waiting for new ones. This is
+    /// where continuously rendering applications draw.
+    fn about_to_wait(&mut self, app: &mut AppContext) {
+        let _ = app;
+    }
+
+    /// Called once when the loop stops, while the windows still exist.
+    fn exit(&mut self, app: &mut AppContext) {
+        let _ = app;
+    }
+}
+
+struct AppWindow {
+    window: PWindow,
+    events: GlfwReceiver<(f64, WindowEvent)>,
+}
+
+/// The state of a running `Glfw::run_app` loop.
+pub struct AppContext {
+    glfw: Glfw,
+    windows: Vec<AppWindow>,
+    control_flow: ControlFlow,
+    exiting: bool,
+}
+
+impl AppContext {
+    /// The `Glfw` token the loop runs on.
+    pub fn glfw(&mut self) -> &mut Glfw {
+        &mut self.glfw
+    }
+
+    /// Creates a window whose events are passed to `ApplicationHandler::window_event`.
+    ///
+    /// Polling is enabled for every kind of event. The window is destroyed once it should
+    /// close, which can be prevented by calling `set_should_close(false)` when handling
+    /// `WindowEvent::Close`.
+    pub fn create_window(
+        &mut self,
+        builder: WindowBuilder<'_>,
+    ) -> Result<WindowId, WindowCreationError> {
+        let (mut window, events) = builder.try_build(&mut self.glfw)?;
+        window.set_all_polling(true);
+        let id = window.window_id();
+        self.windows.push(AppWindow { window, events });
+        Ok(id)
+    }
+
+    /// Returns the window with the given id, if it has not been closed.
+    pub fn window(&self, id: WindowId) -> Option<&Window> {
+        self.windows
+            .iter()
+            .find(|entry| entry.window.window_id() == id)
+            .map(|entry| &*entry.window)
+    }
+
+    /// Returns the window with the given id, if it has not been closed.
+    pub fn window_mut(&mut self, id: WindowId) -> Option<&mut Window> {
+        self.windows
+            .iter_mut()
+            .find(|entry| entry.window.window_id() == id)
+            .map(|entry| &mut *entry.window)
+    }
+
+    /// The ids of the open windows, in the order they were created.
+    pub fn window_ids(&self) -> Vec<WindowId> {
+        self.windows
+            .iter()
+            .map(|entry| entry.window.window_id())
+            .collect()
+    }
+
+    /// Destroys a window immediately. Events it has not delivered yet are discarded.
+    ///
+    /// Returns `false` if there was no open window with this id.
+    pub fn close_window(&mut self, id: WindowId) -> bool {
+        let len = self.windows.len();
+        self.windows.retain(|entry| entry.window.window_id() != id);
+        self.windows.len() != len
+    }
+
+    /// How the loop waits for events.
+    pub fn control_flow(&self) -> ControlFlow {
+        self.control_flow
+    }
+
+    /// Changes how the loop waits for events, starting from the next iteration.
+    pub fn set_control_flow(&mut self, control_flow: ControlFlow) {
+        self.control_flow = control_flow;
+    }
+
+    /// Stops the loop after the current iteration.
+    pub fn exit(&mut self) {
+        self.exiting = true;
+    }
+
+    /// Whether `exit` has been called.
+    pub fn exiting(&self) -> bool {
+        self.exiting
+    }
+
+    fn wait(&mut self) {
+        match self.control_flow {
+            ControlFlow::Poll => self.glfw.poll_events(),
+            ControlFlow::Wait => self.glfw.wait_events(),
+            ControlFlow::WaitTimeout(timeout) => self.glfw.wait_events_timeout(timeout),
+        }
+    }
+}
+
+impl std::fmt::Debug for AppContext {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AppContext")
+            .field("windows", &self.window_ids())
+            .field("control_flow", &self.control_flow)
+            .field("exiting", &self.exiting)
+            .finish()
+    }
+}
+
+enum DeviceEvent {
+    Joystick(JoystickId, JoystickEvent),
+    Monitor(Option<String>, MonitorEvent),
+}
+
+pub(crate) fn run_app<H>(glfw: &mut Glfw, handler: &mut H)
+where
+    H: ApplicationHandler + ?Sized,
+{
+    // The callbacks run during `poll_events`, while the handler cannot be borrowed, so their
+    // events are queued and dispatched afterwards.
+    let device_events = Rc::new(RefCell::new(VecDeque::new()));
+    {
+        let device_events = device_events.clone();
+        glfw.set_joystick_callback(move |id, event| {
+            device_events
+                .borrow_mut()
+                .push_back(DeviceEvent::Joystick(id, event));
+        });
+    }
+    {
+        let device_events = device_events.clone();
+        glfw.set_monitor_callback(move |monitor, event| {
+            device_events
+                .borrow_mut()
+                .push_back(DeviceEvent::Monitor(monitor.get_name(), event));
+        });
+    }
+
+    let mut app = AppContext {
+        glfw: glfw.clone(),
+        windows: Vec::new(),
+        control_flow: ControlFlow::default(),
+        exiting: false,
+    };
+    handler.init(&mut app);
+
+    while !app.exiting && !app.windows.is_empty() {
+        handler.about_to_wait(&mut app);
+        if app.exiting {
+            break;
+        }
+        app.wait();
+
+        loop {
+            // Not held across the dispatch, as the handler may cause further events.
+            let event = device_events.borrow_mut().pop_front();
+            match event {
+                Some(DeviceEvent::Joystick(id, event)) => {
+                    handler.joystick_event(&mut app, id, event)
+                }
+                Some(DeviceEvent::Monitor(name, event)) => {
+                    handler.monitor_event(&mut app, name, event)
+                }
+                None => break,
+            }
+        }
+
+        for id in app.window_ids() {
+            let events: Vec<_> = match app
+                .windows
+                .iter()
+                .find(|entry| entry.window.window_id() == id)
+            {
+                Some(entry) => flush_messages(&entry.events).collect(),
+                // Closed by the handler while dispatching to an earlier window.
+                None => continue,
+            };
+            for (time, event) in events {
+                handler.window_event(&mut app, id, time, event);
+            }
+        }
+
+        app.windows.retain(|entry| !entry.window.should_close());
+    }
+
+    handler.exit(&mut app);
+    drop(app);
+    glfw.unset_joystick_callback();
+    glfw.unset_monitor_callback();
+}
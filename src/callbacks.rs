@@ -65,6 +65,50 @@ pub mod error {
         glfw -> glfwSetErrorCallback(error: c_int, description: *const c_char),
         convert_args -> (mem::transmute(error), crate::string_from_c_str(description))
     );
+
+    thread_local!(static CAPTURED: RefCell<Vec<(crate::Error, String)>> = RefCell::new(Vec::new()));
+
+    /// Restores the previous error callback, and the errors of an enclosing
+    /// `capture`, even if the captured closure panics.
+    struct CaptureGuard {
+        previous_callback: crate::ffi::GLFWerrorfun,
+        outer_errors: Vec<(crate::Error, String)>,
+    }
+
+    impl Drop for CaptureGuard {
+        fn drop(&mut self) {
+            unsafe {
+                crate::ffi::glfwSetErrorCallback(self.previous_callback);
+            }
+            CAPTURED.with(|captured| {
+                *captured.borrow_mut() = mem::take(&mut self.outer_errors);
+            });
+        }
+    }
+
+    extern "C" fn capture_callback(error: c_int, description: *const c_char) {
+        CAPTURED.with(|captured| unsafe {
+            captured.borrow_mut().push((
+                mem::transmute::<c_int, crate::Error>(error),
+                crate::string_from_c_str(description),
+            ))
+        })
+    }
+
+    pub fn capture<T, F>(f: F) -> (T, Vec<(crate::Error, String)>)
+    where
+        F: FnOnce() -> T,
+    {
+        let outer_errors = CAPTURED.with(|captured| mem::take(&mut *captured.borrow_mut()));
+        let previous_callback = unsafe { crate::ffi::glfwSetErrorCallback(Some(capture_callback)) };
+        let _guard = CaptureGuard {
+            previous_callback,
+            outer_errors,
+        };
+        let value = f();
+        let errors = CAPTURED.with(|captured| mem::take(&mut *captured.borrow_mut()));
+        (value, errors)
+    }
 }
 
 pub mod monitor {
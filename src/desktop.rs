@@ -0,0 +1,212 @@
+// Copyright 2013 The GLFW-RS Developers. For a full listing of the authors,
+// refer to the AUTHORS file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Geometry of the virtual desktop spanned by the monitors.
+//!
+//! Rectangles are `(x, y, width, height)` tuples in screen coordinates, like the ones returned
+//! by `Monitor::get_workarea`.
+
+use crate::{MonitorInfo, MonitorRegistry, Window};
+
+type Rect = (i32, i32, i32, i32);
+
+fn saturate(value: i64) -> i32 {
+    value.clamp(i32::MIN as i64, i32::MAX as i64) as i32
+}
+
+fn contains((x, y, width, height): Rect, px: i32, py: i32) -> bool {
+    px >= x
+        && py >= y
+        && (px as i64) < x as i64 + width as i64
+        && (py as i64) < y as i64 + height as i64
+}
+
+pub(crate) fn intersection_area(a: Rect, b: Rect) -> i64 {
+    let overlap = |start_a: i32, len_a: i32, start_b: i32, len_b: i32| {
+        let start = start_a.max(start_b) as i64;
+        let end = (start_a as i64 + len_a as i64).min(start_b as i64 + len_b as i64);
+        (end - start).max(0)
+    };
+    overlap(a.0, a.2, b.0, b.2) * overlap(a.1, a.3, b.1, b.3)
+}
+
+/// The squared distance from a point to the nearest point of a rectangle.
+fn distance_squared((x, y, width, height): Rect, px: i32, py: i32) -> i64 {
+    let axis = |p: i32, start: i32, len: i32| {
+        let (p, start, end) = (p as i64, start as i64, start as i64 + len as i64);
+        if p < start {
+            start - p
+        } else if p > end {
+            p - end
+        } else {
+            0
+        }
+    };
+    let dx = axis(px, x, width);
+    let dy = axis(py, y, height);
+    dx * dx + dy * dy
+}
+
+/// Fits `len` into `[start, start + available)`, shrinking it if it does not fit.
+fn clamp_axis(pos: i32, len: i32, start: i32, available: i32) -> (i32, i32) {
+    let len = len.min(available).max(0);
+    let end = start as i64 + available as i64 - len as i64;
+    let pos = (pos as i64).max(start as i64).min(end);
+    (saturate(pos), len)
+}
+
+impl MonitorInfo {
+    /// The bounds of the monitor on the virtual desktop, from its position and video mode.
+    ///
+    /// Monitors without a video mode are taken to be empty.
+    pub fn bounds(&self) -> (i32, i32, i32, i32) {
+        let (width, height) = self
+            .video_mode
+            .map_or((0, 0), |mode| (mode.width as i32, mode.height as i32));
+        (self.pos.0, self.pos.1, width, height)
+    }
+}
+
+/// A snapshot of the arrangement of the monitors on the virtual desktop.
+///
+/// # Example
+///
+/// ~~~ignore
+/// let registry = glfw::MonitorRegistry::new(&mut glfw);
+/// let layout = glfw::DesktopLayout::from_registry(&registry);
+/// if let Some(monitor) = layout.monitor_for_window(&window) {
+///     println!("window is on {:?}", monitor.name);
+/// }
+/// layout.clamp_window(&mut window);
+/// ~~~
+#[derive(Clone, PartialEq, Debug)]
+pub struct DesktopLayout {
+    monitors: Vec<MonitorInfo>,
+}
+
+impl DesktopLayout {
+    /// Creates a layout of the monitors given.
+    pub fn new(monitors: Vec<MonitorInfo>) -> DesktopLayout {
+        DesktopLayout { monitors }
+    }
+
+    /// Creates a layout of the monitors a registry has captured.
+    pub fn from_registry(registry: &MonitorRegistry) -> DesktopLayout {
+        DesktopLayout::new(registry.monitors())
+    }
+
+    /// The monitors in the layout.
+    pub fn monitors(&self) -> &[MonitorInfo] {
+        &self.monitors
+    }
+
+    /// The smallest rectangle containing every monitor, or `None` if there are none. Its size
+    /// saturates at `i32::MAX` for monitors spread further apart.
+    pub fn bounds(&self) -> Option<(i32, i32, i32, i32)> {
+        self.monitors
+            .iter()
+            .map(MonitorInfo::bounds)
+            .fold(None, |union, rect| {
+                Some(match union {
+                    None => rect,
+                    Some((x, y, width, height)) => {
+                        let end = |start: i32, len: i32| start as i64 + len as i64;
+                        let left = x.min(rect.0);
+                        let top = y.min(rect.1);
+                        let right = end(x, width).max(end(rect.0, rect.2));
+                        let bottom = end(y, height).max(end(rect.1, rect.3));
+                        (
+                            left,
+                            top,
+                            saturate(right - left as i64),
+                            saturate(bottom - top as i64),
+                        )
+                    }
+                })
+            })
+    }
+
+    /// The monitor containing the point, if any.
+    pub fn monitor_at(&self, x: i32, y: i32) -> Option<&MonitorInfo> {
+        self.monitors
+            .iter()
+            .find(|info| contains(info.bounds(), x, y))
+    }
+
+    /// The monitor nearest to the point, which is the one containing it if there is one.
+    pub fn nearest_monitor(&self, x: i32, y: i32) -> Option<&MonitorInfo> {
+        self.monitors
+            .iter()
+            .min_by_key(|info| distance_squared(info.bounds(), x, y))
+    }
+
+    /// The monitor the rectangle overlaps the most, if it overlaps any.
+    pub fn monitor_for_rect(&self, rect: (i32, i32, i32, i32)) -> Option<&MonitorInfo> {
+        // Reversed, as `max_by_key` picks the last of equally overlapped monitors.
+        self.monitors
+            .iter()
+            .rev()
+            .map(|info| (intersection_area(info.bounds(), rect), info))
+            .max_by_key(|&(area, _)| area)
+            .filter(|&(area, _)| area > 0)
+            .map(|(_, info)| info)
+    }
+
+    /// The monitor the content area of the window overlaps the most, if it overlaps any.
+    ///
+    /// Unlike `Window::with_window_mode`, this also works for windowed mode windows.
+    pub fn monitor_for_window(&self, window: &Window) -> Option<&MonitorInfo> {
+        let (x, y) = window.get_pos();
+        let (width, height) = window.get_size();
+        self.monitor_for_rect((x, y, width, height))
+    }
+
+    /// Moves and if necessary shrinks the rectangle so that it lies within the workarea of the
+    /// monitor it overlaps the most, or of the monitor nearest to its centre if it overlaps none.
+    ///
+    /// Returns the rectangle unchanged if there are no monitors.
+    pub fn clamp_to_workarea(&self, rect: (i32, i32, i32, i32)) -> (i32, i32, i32, i32) {
+        let (x, y, width, height) = rect;
+        let monitor = self.monitor_for_rect(rect).or_else(|| {
+            let centre = |start: i32, len: i32| saturate(start as i64 + len as i64 / 2);
+            self.nearest_monitor(centre(x, width), centre(y, height))
+        });
+        match monitor {
+            Some(info) => {
+                let (wx, wy, wwidth, wheight) = info.workarea;
+                let (x, width) = clamp_axis(x, width, wx, wwidth);
+                let (y, height) = clamp_axis(y, height, wy, wheight);
+                (x, y, width, height)
+            }
+            None => rect,
+        }
+    }
+
+    /// Moves and if necessary resizes the window so that its content area lies within a
+    /// workarea, as decided by `clamp_to_workarea`.
+    ///
+    /// Window decorations are not taken into account.
+    pub fn clamp_window(&self, window: &mut Window) {
+        let (x, y) = window.get_pos();
+        let (width, height) = window.get_size();
+        let (new_x, new_y, new_width, new_height) = self.clamp_to_workarea((x, y, width, height));
+        if (new_width, new_height) != (width, height) {
+            window.set_size(new_width, new_height);
+        }
+        if (new_x, new_y) != (x, y) {
+            window.set_pos(new_x, new_y);
+        }
+    }
+}
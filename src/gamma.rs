@@ -0,0 +1,308 @@
+// Copyright 2013 The GLFW-RS Developers. For a full listing of the authors,
+// refer to the AUTHORS file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Construction and validated application of gamma ramps.
+
+use std::{error, fmt, slice};
+
+use crate::{ffi, GammaRamp, Monitor};
+
+/// An error that might be returned when reading or setting a gamma ramp.
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub enum GammaError {
+    /// The platform does not support gamma ramps, as is the case on Wayland.
+    Unavailable,
+    /// The red, green and blue channels do not have the same number of entries, or have none.
+    MismatchedChannels,
+    /// The ramp does not have as many entries as the one it has to match.
+    WrongSize { expected: usize, actual: usize },
+}
+
+impl fmt::Display for GammaError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            GammaError::Unavailable => f.write_str("Gamma ramps are not available"),
+            GammaError::MismatchedChannels => f.write_str("Gamma ramp channels differ in size"),
+            GammaError::WrongSize { expected, actual } => write!(
+                f,
+                "Gamma ramp has {} entries instead of {}",
+                actual, expected
+            ),
+        }
+    }
+}
+
+impl error::Error for GammaError {}
+
+fn to_entry(value: f32) -> u16 {
+    (value.clamp(0.0, 1.0) * 65535.0 + 0.5) as u16
+}
+
+/// The input value of each entry of a ramp with `size` entries, from 0 to 1.
+fn inputs(size: usize) -> impl Iterator<Item = f32> {
+    let last = size.saturating_sub(1).max(1) as f32;
+    (0..size).map(move |i| i as f32 / last)
+}
+
+/// The white point of a black body at `kelvin`, scaled so that 6500 K is white.
+fn white_point(kelvin: f32) -> (f32, f32, f32) {
+    // Tanner Helland's fit of the black body colors, in 0-255 units.
+    fn fit(kelvin: f32) -> (f32, f32, f32) {
+        let t = kelvin.clamp(1000.0, 40000.0) / 100.0;
+        let red = if t <= 66.0 {
+            255.0
+        } else {
+            329.698_73 * (t - 60.0).powf(-0.133_204_76)
+        };
+        let green = if t <= 66.0 {
+            99.470_8 * t.ln() - 161.119_57
+        } else {
+            288.122_16 * (t - 60.0).powf(-0.075_514_85)
+        };
+        let blue = if t >= 66.0 {
+            255.0
+        } else if t <= 19.0 {
+            0.0
+        } else {
+            138.517_73 * (t - 10.0).ln() - 305.044_8
+        };
+        (red, green, blue)
+    }
+
+    let (red, green, blue) = fit(kelvin);
+    let (white_red, white_green, white_blue) = fit(6500.0);
+    (
+        (red / white_red).clamp(0.0, 1.0),
+        (green / white_green).clamp(0.0, 1.0),
+        (blue / white_blue).clamp(0.0, 1.0),
+    )
+}
+
+impl GammaRamp {
+    /// Creates a ramp with the same curve on every channel, `f` mapping inputs from 0 to 1 to
+    /// outputs from 0 to 1.
+    pub fn from_fn<F>(size: usize, f: F) -> GammaRamp
+    where
+        F: Fn(f32) -> f32,
+    {
+        let channel: Vec<u16> = inputs(size).map(|x| to_entry(f(x))).collect();
+        GammaRamp {
+            red: channel.clone(),
+            green: channel.clone(),
+            blue: channel,
+        }
+    }
+
+    /// Creates a ramp that leaves colors unchanged.
+    pub fn identity(size: usize) -> GammaRamp {
+        GammaRamp::from_fn(size, |x| x)
+    }
+
+    /// Creates a ramp for the gamma exponent `gamma`, with the curve `glfwSetGamma` uses.
+    ///
+    /// # Panics
+    ///
+    /// If `gamma` is not positive.
+    pub fn from_gamma(size: usize, gamma: f32) -> GammaRamp {
+        assert!(gamma > 0.0, "gamma must be positive");
+        GammaRamp::from_fn(size, |x| x.powf(1.0 / gamma))
+    }
+
+    /// Creates a ramp adding `brightness` to every value and scaling the distance from mid grey
+    /// by `contrast`. A brightness of 0 and a contrast of 1 leave colors unchanged.
+    pub fn from_brightness_contrast(size: usize, brightness: f32, contrast: f32) -> GammaRamp {
+        GammaRamp::from_fn(size, |x| (x - 0.5) * contrast + 0.5 + brightness)
+    }
+
+    /// Creates a ramp tinting colors like light of a black body at `kelvin`, approximately.
+    /// 6500 K leaves colors unchanged, lower temperatures are warmer and higher ones cooler.
+    pub fn from_color_temperature(size: usize, kelvin: f32) -> GammaRamp {
+        let (red, green, blue) = white_point(kelvin);
+        let channel = |scale: f32| inputs(size).map(|x| to_entry(x * scale)).collect();
+        GammaRamp {
+            red: channel(red),
+            green: channel(green),
+            blue: channel(blue),
+        }
+    }
+
+    /// Creates a ramp by resampling a lookup table with any number of entries to `size` entries,
+    /// interpolating linearly between them. The table is used for every channel.
+    ///
+    /// # Panics
+    ///
+    /// If `lut` is empty.
+    pub fn from_lut(size: usize, lut: &[u16]) -> GammaRamp {
+        assert!(!lut.is_empty(), "lookup table must not be empty");
+        let last = (lut.len() - 1) as f32;
+        GammaRamp::from_fn(size, |x| {
+            let pos = x * last;
+            let index = pos.floor() as usize;
+            let next = (index + 1).min(lut.len() - 1);
+            let a = lut[index] as f32;
+            let b = lut[next] as f32;
+            (a + (b - a) * (pos - index as f32)) / 65535.0
+        })
+    }
+
+    /// Creates a ramp from separate channels.
+    ///
+    /// # Returns
+    ///
+    /// `Err(GammaError::MismatchedChannels)` if the channels differ in size or are empty.
+    pub fn from_channels(
+        red: Vec<u16>,
+        green: Vec<u16>,
+        blue: Vec<u16>,
+    ) -> Result<GammaRamp, GammaError> {
+        let ramp = GammaRamp { red, green, blue };
+        ramp.validate()?;
+        Ok(ramp)
+    }
+
+    /// The number of entries of each channel, if they agree.
+    pub fn size(&self) -> Option<usize> {
+        self.validate().ok().map(|()| self.red.len())
+    }
+
+    /// Checks that the channels have the same, non-zero number of entries.
+    pub fn validate(&self) -> Result<(), GammaError> {
+        let size = self.red.len();
+        if size == 0 || self.green.len() != size || self.blue.len() != size {
+            return Err(GammaError::MismatchedChannels);
+        }
+        Ok(())
+    }
+
+    /// Interpolates linearly between this ramp, at `t = 0`, and `other`, at `t = 1`, for fading
+    /// from one to the other.
+    ///
+    /// # Returns
+    ///
+    /// An error if either ramp is invalid, or the two differ in size.
+    pub fn lerp(&self, other: &GammaRamp, t: f32) -> Result<GammaRamp, GammaError> {
+        self.validate()?;
+        other.validate()?;
+        if other.red.len() != self.red.len() {
+            return Err(GammaError::WrongSize {
+                expected: self.red.len(),
+                actual: other.red.len(),
+            });
+        }
+        let t = t.clamp(0.0, 1.0);
+        let channel = |from: &[u16], to: &[u16]| {
+            from.iter()
+                .zip(to)
+                .map(|(&a, &b)| (a as f32 + (b as f32 - a as f32) * t + 0.5) as u16)
+                .collect()
+        };
+        Ok(GammaRamp {
+            red: channel(&self.red, &other.red),
+            green: channel(&self.green, &other.green),
+            blue: channel(&self.blue, &other.blue),
+        })
+    }
+}
+
+impl Monitor {
+    /// Like `get_gamma_ramp`, but reports an error rather than crashing on platforms without
+    /// gamma ramps.
+    ///
+    /// Wrapper for `glfwGetGammaRamp`.
+    pub fn try_get_gamma_ramp(&self) -> Result<GammaRamp, GammaError> {
+        unsafe {
+            let ramp = ffi::glfwGetGammaRamp(self.ptr);
+            if ramp.is_null() {
+                return Err(GammaError::Unavailable);
+            }
+            let ramp = &*ramp;
+            let channel = |ptr: *mut u16| slice::from_raw_parts(ptr, ramp.size as usize).to_vec();
+            Ok(GammaRamp {
+                red: channel(ramp.red),
+                green: channel(ramp.green),
+                blue: channel(ramp.blue),
+            })
+        }
+    }
+
+    /// Like `set_gamma_ramp`, but checks the ramp first. As some platforms require it, the ramp
+    /// must have as many entries as the current one.
+    ///
+    /// Wrapper for `glfwSetGammaRamp`.
+    pub fn try_set_gamma_ramp(&mut self, ramp: &GammaRamp) -> Result<(), GammaError> {
+        ramp.validate()?;
+        let expected = self.try_get_gamma_ramp()?.red.len();
+        if ramp.red.len() != expected {
+            return Err(GammaError::WrongSize {
+                expected,
+                actual: ramp.red.len(),
+            });
+        }
+        self.set_gamma_ramp(&mut ramp.clone());
+        Ok(())
+    }
+
+    /// Saves the current gamma ramp, which the returned guard restores when it is dropped, even
+    /// while unwinding from a panic.
+    ///
+    /// # Example
+    ///
+    /// ~~~ignore
+    /// glfw.with_primary_monitor(|glfw, monitor| {
+    ///     let mut guard = monitor.unwrap().gamma_guard()?;
+    ///     let dark = glfw::GammaRamp::from_brightness_contrast(guard.original().red.len(), -0.5, 1.0);
+    ///     for step in 0..=30 {
+    ///         guard.set_ramp(&guard.original().lerp(&dark, step as f32 / 30.0)?)?;
+    ///         thread::sleep(Duration::from_millis(16));
+    ///     }
+    ///     run_cutscene();
+    ///     Ok(())
+    /// })
+    /// ~~~
+    pub fn gamma_guard(&mut self) -> Result<GammaGuard<'_>, GammaError> {
+        let original = self.try_get_gamma_ramp()?;
+        Ok(GammaGuard {
+            monitor: self,
+            original,
+        })
+    }
+}
+
+/// Restores the gamma ramp of a monitor when dropped.
+///
+/// Returned by `Monitor::gamma_guard`.
+#[derive(Debug)]
+pub struct GammaGuard<'a> {
+    monitor: &'a mut Monitor,
+    original: GammaRamp,
+}
+
+impl<'a> GammaGuard<'a> {
+    /// The ramp that will be restored.
+    pub fn original(&self) -> &GammaRamp {
+        &self.original
+    }
+
+    /// Sets the gamma ramp of the monitor, see `Monitor::try_set_gamma_ramp`.
+    pub fn set_ramp(&mut self, ramp: &GammaRamp) -> Result<(), GammaError> {
+        self.monitor.try_set_gamma_ramp(ramp)
+    }
+}
+
+impl<'a> Drop for GammaGuard<'a> {
+    fn drop(&mut self) {
+        self.monitor.set_gamma_ramp(&mut self.original);
+    }
+}
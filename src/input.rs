@@ -0,0 +1,400 @@
+// Copyright 2013 The GLFW-RS Developers. For a full listing of the authors,
+// refer to the AUTHORS file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Keyboard and mouse state tracking.
+
+use std::collections::HashMap;
+use std::hash::Hash;
+
+use crate::{Action, Key, Modifiers, MouseButton, Window, WindowEvent};
+
+/// Every key `glfwGetKey` accepts.
+const KEYS: [Key; 120] = [
+    Key::Space,
+    Key::Apostrophe,
+    Key::Comma,
+    Key::Minus,
+    Key::Period,
+    Key::Slash,
+    Key::Num0,
+    Key::Num1,
+    Key::Num2,
+    Key::Num3,
+    Key::Num4,
+    Key::Num5,
+    Key::Num6,
+    Key::Num7,
+    Key::Num8,
+    Key::Num9,
+    Key::Semicolon,
+    Key::Equal,
+    Key::A,
+    Key::B,
+    Key::C,
+    Key::D,
+    Key::E,
+    Key::F,
+    Key::G,
+    Key::H,
+    Key::I,
+    Key::J,
+    Key::K,
+    Key::L,
+    Key::M,
+    Key::N,
+    Key::O,
+    Key::P,
+    Key::Q,
+    Key::R,
+    Key::S,
+    Key::T,
+    Key::U,
+    Key::V,
+    Key::W,
+    Key::X,
+    Key::Y,
+    Key::Z,
+    Key::LeftBracket,
+    Key::Backslash,
+    Key::RightBracket,
+    Key::GraveAccent,
+    Key::World1,
+    Key::World2,
+    Key::Escape,
+    Key::Enter,
+    Key::Tab,
+    Key::Backspace,
+    Key::Insert,
+    Key::Delete,
+    Key::Right,
+    Key::Left,
+    Key::Down,
+    Key::Up,
+    Key::PageUp,
+    Key::PageDown,
+    Key::Home,
+    Key::End,
+    Key::CapsLock,
+    Key::ScrollLock,
+    Key::NumLock,
+    Key::PrintScreen,
+    Key::Pause,
+    Key::F1,
+    Key::F2,
+    Key::F3,
+    Key::F4,
+    Key::F5,
+    Key::F6,
+    Key::F7,
+    Key::F8,
+    Key::F9,
+    Key::F10,
+    Key::F11,
+    Key::F12,
+    Key::F13,
+    Key::F14,
+    Key::F15,
+    Key::F16,
+    Key::F17,
+    Key::F18,
+    Key::F19,
+    Key::F20,
+    Key::F21,
+    Key::F22,
+    Key::F23,
+    Key::F24,
+    Key::F25,
+    Key::Kp0,
+    Key::Kp1,
+    Key::Kp2,
+    Key::Kp3,
+    Key::Kp4,
+    Key::Kp5,
+    Key::Kp6,
+    Key::Kp7,
+    Key::Kp8,
+    Key::Kp9,
+    Key::KpDecimal,
+    Key::KpDivide,
+    Key::KpMultiply,
+    Key::KpSubtract,
+    Key::KpAdd,
+    Key::KpEnter,
+    Key::KpEqual,
+    Key::LeftShift,
+    Key::LeftControl,
+    Key::LeftAlt,
+    Key::LeftSuper,
+    Key::RightShift,
+    Key::RightControl,
+    Key::RightAlt,
+    Key::RightSuper,
+    Key::Menu,
+];
+
+const MOUSE_BUTTONS: [MouseButton; 8] = [
+    MouseButton::Button1,
+    MouseButton::Button2,
+    MouseButton::Button3,
+    MouseButton::Button4,
+    MouseButton::Button5,
+    MouseButton::Button6,
+    MouseButton::Button7,
+    MouseButton::Button8,
+];
+
+#[derive(Copy, Clone, Default, Debug)]
+struct ButtonState {
+    /// The time of the press, while the button is held.
+    down_since: Option<f64>,
+    pressed: bool,
+    released: bool,
+}
+
+#[derive(Debug)]
+struct Buttons<B> {
+    states: HashMap<B, ButtonState>,
+}
+
+impl<B: Copy + Eq + Hash> Buttons<B> {
+    fn new() -> Buttons<B> {
+        Buttons {
+            states: HashMap::new(),
+        }
+    }
+
+    fn press(&mut self, button: B, time: f64) {
+        let state = self.states.entry(button).or_default();
+        if state.down_since.is_none() {
+            state.down_since = Some(time);
+            state.pressed = true;
+        }
+    }
+
+    fn release(&mut self, button: B) {
+        if let Some(state) = self.states.get_mut(&button) {
+            if state.down_since.take().is_some() {
+                state.released = true;
+            }
+        }
+    }
+
+    fn handle(&mut self, button: B, action: Action, time: f64) {
+        match action {
+            Action::Press | Action::Repeat => self.press(button, time),
+            Action::Release => self.release(button),
+        }
+    }
+
+    fn release_all(&mut self) {
+        for state in self.states.values_mut() {
+            if state.down_since.take().is_some() {
+                state.released = true;
+            }
+        }
+    }
+
+    fn begin_frame(&mut self) {
+        self.states.retain(|_, state| {
+            state.pressed = false;
+            state.released = false;
+            state.down_since.is_some()
+        });
+    }
+
+    fn get(&self, button: B) -> ButtonState {
+        self.states.get(&button).copied().unwrap_or_default()
+    }
+}
+
+/// Keyboard and mouse state, built from the events of a window.
+///
+/// Feed it every event flushed from the window's receiver and call `begin_frame` once per frame,
+/// before flushing. Key and mouse button events must be polled for it to see them, as must
+/// `CursorPos`, `Scroll` and `Focus` events for the corresponding state.
+///
+/// # Example
+///
+/// ~~~ignore
+/// let mut input = glfw::InputState::new();
+/// while !window.should_close() {
+///     input.begin_frame();
+///     glfw.poll_events();
+///     for (time, event) in glfw::flush_messages(&events) {
+///         input.handle_event(time, &event);
+///     }
+///     if input.was_key_pressed(glfw::Key::Space) {
+///         jump();
+///     }
+///     camera.rotate(input.cursor_delta());
+/// }
+/// ~~~
+#[derive(Debug)]
+pub struct InputState {
+    keys: Buttons<Key>,
+    mouse_buttons: Buttons<MouseButton>,
+    modifiers: Modifiers,
+    cursor_pos: Option<(f64, f64)>,
+    cursor_delta: (f64, f64),
+    scroll: (f64, f64),
+}
+
+impl Default for InputState {
+    fn default() -> InputState {
+        InputState::new()
+    }
+}
+
+impl InputState {
+    /// Creates a state with nothing held.
+    pub fn new() -> InputState {
+        InputState {
+            keys: Buttons::new(),
+            mouse_buttons: Buttons::new(),
+            modifiers: Modifiers::empty(),
+            cursor_pos: None,
+            cursor_delta: (0.0, 0.0),
+            scroll: (0.0, 0.0),
+        }
+    }
+
+    /// Starts a new frame, clearing the presses and releases, cursor movement and scrolling of
+    /// the previous one.
+    pub fn begin_frame(&mut self) {
+        self.keys.begin_frame();
+        self.mouse_buttons.begin_frame();
+        self.cursor_delta = (0.0, 0.0);
+        self.scroll = (0.0, 0.0);
+    }
+
+    /// Updates the state with an event flushed from a window's receiver, along with its
+    /// timestamp.
+    pub fn handle_event(&mut self, time: f64, event: &WindowEvent) {
+        match *event {
+            WindowEvent::Key(Key::Unknown, _, _, mods) => self.modifiers = mods,
+            WindowEvent::Key(key, _, action, mods) => {
+                self.keys.handle(key, action, time);
+                self.modifiers = mods;
+            }
+            WindowEvent::MouseButton(button, action, mods) => {
+                self.mouse_buttons.handle(button, action, time);
+                self.modifiers = mods;
+            }
+            WindowEvent::CharModifiers(_, mods) => self.modifiers = mods,
+            WindowEvent::CursorPos(x, y) => {
+                if let Some((last_x, last_y)) = self.cursor_pos {
+                    self.cursor_delta.0 += x - last_x;
+                    self.cursor_delta.1 += y - last_y;
+                }
+                self.cursor_pos = Some((x, y));
+            }
+            WindowEvent::Scroll(x, y) => {
+                self.scroll.0 += x;
+                self.scroll.1 += y;
+            }
+            WindowEvent::Focus(false) => {
+                self.keys.release_all();
+                self.mouse_buttons.release_all();
+                self.modifiers = Modifiers::empty();
+            }
+            _ => {}
+        }
+    }
+
+    /// Brings the state in line with `Window::get_key`, `Window::get_mouse_button` and
+    /// `Window::get_cursor_pos`.
+    ///
+    /// Useful when the window regains focus, or after events were flushed without being handed
+    /// to this state. Differences are recorded as presses and releases in the current frame.
+    pub fn resync(&mut self, window: &Window) {
+        let time = window.glfw.get_time();
+        for &key in KEYS.iter() {
+            match window.get_key(key) {
+                Action::Release => self.keys.release(key),
+                _ => self.keys.press(key, time),
+            }
+        }
+        for &button in MOUSE_BUTTONS.iter() {
+            match window.get_mouse_button(button) {
+                Action::Release => self.mouse_buttons.release(button),
+                _ => self.mouse_buttons.press(button, time),
+            }
+        }
+        self.cursor_pos = Some(window.get_cursor_pos());
+    }
+
+    /// Whether the key is held down.
+    pub fn is_key_down(&self, key: Key) -> bool {
+        self.keys.get(key).down_since.is_some()
+    }
+
+    /// Whether the key was pressed during the current frame.
+    pub fn was_key_pressed(&self, key: Key) -> bool {
+        self.keys.get(key).pressed
+    }
+
+    /// Whether the key was released during the current frame.
+    pub fn was_key_released(&self, key: Key) -> bool {
+        self.keys.get(key).released
+    }
+
+    /// How long the key has been held at time `now`, or `None` if it is not held.
+    pub fn key_held_for(&self, key: Key, now: f64) -> Option<f64> {
+        self.keys.get(key).down_since.map(|since| now - since)
+    }
+
+    /// Whether the mouse button is held down.
+    pub fn is_mouse_button_down(&self, button: MouseButton) -> bool {
+        self.mouse_buttons.get(button).down_since.is_some()
+    }
+
+    /// Whether the mouse button was pressed during the current frame.
+    pub fn was_mouse_button_pressed(&self, button: MouseButton) -> bool {
+        self.mouse_buttons.get(button).pressed
+    }
+
+    /// Whether the mouse button was released during the current frame.
+    pub fn was_mouse_button_released(&self, button: MouseButton) -> bool {
+        self.mouse_buttons.get(button).released
+    }
+
+    /// How long the mouse button has been held at time `now`, or `None` if it is not held.
+    pub fn mouse_button_held_for(&self, button: MouseButton, now: f64) -> Option<f64> {
+        self.mouse_buttons
+            .get(button)
+            .down_since
+            .map(|since| now - since)
+    }
+
+    /// The modifiers reported by the latest key, mouse button or character event.
+    pub fn modifiers(&self) -> Modifiers {
+        self.modifiers
+    }
+
+    /// The last known cursor position, or `None` if none has been reported yet.
+    pub fn cursor_pos(&self) -> Option<(f64, f64)> {
+        self.cursor_pos
+    }
+
+    /// How far the cursor moved during the current frame.
+    pub fn cursor_delta(&self) -> (f64, f64) {
+        self.cursor_delta
+    }
+
+    /// The scrolling accumulated during the current frame.
+    pub fn scroll(&self) -> (f64, f64) {
+        self.scroll
+    }
+}
@@ -75,6 +75,20 @@
 //!
 //! Use the `vulkan` feature flag to enable all Vulkan functions and types.
 //!
+//! Use the `ash` feature flag to enable the Vulkan helpers built on the [`ash`](https://github.com/ash-rs/ash) crate.
+//! It implies `vulkan`.
+//!
+//! Use the `glow` feature flag to load OpenGL functions into a [`glow`](https://github.com/grovesNL/glow) context with `Context::glow_context`.
+//!
+//! Use the `testing` feature flag to enable the `testing` module, for running code against
+//! GLFW's `Null` platform in headless tests.
+//!
+//! Use the `recording` feature flag to enable the `recording` module, for recording input
+//! events to a file and replaying them. It implies `serde`.
+//!
+//! Use the `futures` feature flag to make `GlfwReceiver` a `futures_core::Stream`, for
+//! awaiting events in async code.
+//!
 //! Use the `image` feature flag to enable use of the [`image`](https://github.com/PistonDevelopers/image) library for cursors and icons.
 //!
 //! Use the `all` feature flag to enable both at the same time.
@@ -223,6 +237,24 @@ macro_rules! new_callback {
     }
 }
 
+macro_rules! checked_methods {
+    (
+        $(
+            $(#[$attr:meta])*
+            fn $name:ident($($arg_names:ident: $args:ty),*) -> $ret:ty;
+        )*
+    ) => {
+        $(
+            $(#[$attr])*
+            #[doc = concat!("Checked version of `Window::", stringify!($name), "`.")]
+            pub fn $name(&mut self, $($arg_names: $args),*) -> Result<$ret, Error> {
+                let window = &mut *self.window;
+                check_error(move || window.$name($($arg_names),*))
+            }
+        )*
+    }
+}
+
 #[cfg(feature = "log")]
 #[macro_use]
 extern crate log;
@@ -253,6 +285,13 @@ use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::mpsc::{channel, Receiver, Sender};
 use std::sync::{Arc, Mutex};
 use std::{error, fmt, mem, ptr, slice};
+#[cfg(feature = "futures")]
+use std::{
+    future::Future,
+    pin::Pin,
+    sync::mpsc::TryRecvError,
+    task::{self, Poll, Waker},
+};
 
 #[cfg(feature = "raw-window-handle-v0-6")]
 use raw_window_handle::{
@@ -270,9 +309,33 @@ pub use self::MouseButton::Button1 as MouseButtonLeft;
 pub use self::MouseButton::Button2 as MouseButtonRight;
 /// Alias to `MouseButton3`, supplied for improved clarity.
 pub use self::MouseButton::Button3 as MouseButtonMiddle;
+
+pub use self::allocator::{init_allocator, CountingAllocator};
+pub use self::desktop::DesktopLayout;
+pub use self::gamma::{GammaError, GammaGuard};
+pub use self::input::InputState;
+pub use self::monitor_registry::{MonitorError, MonitorId, MonitorInfo, MonitorRegistry};
+pub use self::placement::{FullscreenPlacement, WindowPlacement};
+pub use self::timing::{FrameClock, FrameLimiter};
+pub use self::video_mode::VideoModeQuery;
 use crate::ffi::GLFWwindow;
+use crate::spin_lock::SpinLock;
 
+mod allocator;
+pub mod app;
 mod callbacks;
+mod desktop;
+mod gamma;
+mod input;
+mod monitor_registry;
+mod placement;
+#[cfg(feature = "recording")]
+pub mod recording;
+mod spin_lock;
+#[cfg(feature = "testing")]
+pub mod testing;
+mod timing;
+mod video_mode;
 
 #[derive(Debug)]
 #[repr(transparent)]
@@ -606,6 +669,10 @@ pub enum Error {
     PlatformError = ffi::GLFW_PLATFORM_ERROR,
     FormatUnavailable = ffi::GLFW_FORMAT_UNAVAILABLE,
     NoWindowContext = ffi::GLFW_NO_WINDOW_CONTEXT,
+    CursorUnavailable = ffi::GLFW_CURSOR_UNAVAILABLE,
+    FeatureUnavailable = ffi::GLFW_FEATURE_UNAVAILABLE,
+    FeatureUnimplemented = ffi::GLFW_FEATURE_UNIMPLEMENTED,
+    PlatformUnavailable = ffi::GLFW_PLATFORM_UNAVAILABLE,
 }
 
 impl fmt::Display for Error {
@@ -622,6 +689,10 @@ impl fmt::Display for Error {
             Error::PlatformError => "PlatformError",
             Error::FormatUnavailable => "FormatUnavailable",
             Error::NoWindowContext => "NoWindowContext",
+            Error::CursorUnavailable => "CursorUnavailable",
+            Error::FeatureUnavailable => "FeatureUnavailable",
+            Error::FeatureUnimplemented => "FeatureUnimplemented",
+            Error::PlatformUnavailable => "PlatformUnavailable",
         };
 
         f.write_str(description)
@@ -696,6 +767,8 @@ pub enum CursorMode {
     Normal = ffi::GLFW_CURSOR_NORMAL,
     Hidden = ffi::GLFW_CURSOR_HIDDEN,
     Disabled = ffi::GLFW_CURSOR_DISABLED,
+    /// The cursor is visible but confined to the content area of the window.
+    Captured = ffi::GLFW_CURSOR_CAPTURED,
 }
 
 /// Standard cursors provided by GLFW
@@ -709,12 +782,20 @@ pub enum StandardCursor {
     IBeam = ffi::GLFW_IBEAM_CURSOR,
     /// The crosshair shape.
     Crosshair = ffi::GLFW_CROSSHAIR_CURSOR,
-    /// The hand shape.
-    Hand = ffi::GLFW_HAND_CURSOR,
+    /// The pointing hand shape.
+    Hand = ffi::GLFW_POINTING_HAND_CURSOR,
     /// The horizontal resize arrow shape.
-    HResize = ffi::GLFW_HRESIZE_CURSOR,
+    HResize = ffi::GLFW_RESIZE_EW_CURSOR,
     /// The vertical resize arrow shape.
-    VResize = ffi::GLFW_VRESIZE_CURSOR,
+    VResize = ffi::GLFW_RESIZE_NS_CURSOR,
+    /// The top-left to bottom-right diagonal resize arrow shape.
+    ResizeNWSE = ffi::GLFW_RESIZE_NWSE_CURSOR,
+    /// The top-right to bottom-left diagonal resize arrow shape.
+    ResizeNESW = ffi::GLFW_RESIZE_NESW_CURSOR,
+    /// The omni-directional resize, or move, cursor shape.
+    ResizeAll = ffi::GLFW_RESIZE_ALL_CURSOR,
+    /// The operation-not-allowed shape.
+    NotAllowed = ffi::GLFW_NOT_ALLOWED_CURSOR,
 }
 
 /// Represents a window cursor that can be used to display any
@@ -735,12 +816,33 @@ impl Drop for Cursor {
 
 impl Cursor {
     /// Create a new cursor using `glfwCreateStandardCursor`
+    ///
+    /// Not every shape is available on every platform, and an unavailable one results in an
+    /// invalid cursor. Use `Cursor::try_standard` to detect this.
     pub fn standard(cursor: StandardCursor) -> Cursor {
         Cursor {
             ptr: unsafe { ffi::glfwCreateStandardCursor(cursor as c_int) },
         }
     }
 
+    /// Create a new cursor using `glfwCreateStandardCursor`, reporting why it could not be
+    /// created.
+    ///
+    /// The error is also passed to the error callback, as usual.
+    ///
+    /// # Returns
+    ///
+    /// `Err(Error::CursorUnavailable)` if the platform has no cursor of this shape, or the error
+    /// GLFW reported otherwise.
+    pub fn try_standard(cursor: StandardCursor) -> Result<Cursor, Error> {
+        let ptr = check_error(|| unsafe { ffi::glfwCreateStandardCursor(cursor as c_int) })?;
+        if ptr.is_null() {
+            Err(Error::CursorUnavailable)
+        } else {
+            Ok(Cursor { ptr })
+        }
+    }
+
     /// Creates a new cursor from the image provided via `glfwCreateCursor`
     ///
     /// Note that the cursor image will be the same size as the image provided,
@@ -800,7 +902,9 @@ impl Cursor {
 }
 
 /// Describes a single video mode.
-#[derive(Copy, Clone)]
+///
+/// Modes are ordered by width, then height, then bit depths, then refresh rate.
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct VidMode {
     pub width: u32,
@@ -812,7 +916,10 @@ pub struct VidMode {
 }
 
 /// Describes the gamma ramp of a monitor.
-#[derive(Debug)]
+///
+/// Each channel maps evenly spaced input values to output values, with `u16::MAX` for full
+/// intensity. All three channels must have the same number of entries.
+#[derive(Clone, Eq, PartialEq, Debug)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct GammaRamp {
     pub red: Vec<c_ushort>,
@@ -863,6 +970,9 @@ pub enum SwapInterval {
 /// An OpenGL process address.
 pub type GLProc = ffi::GLFWglproc;
 
+/// An OpenGL function loader, returned by `Context::gl_loader`.
+pub type GlLoader = Box<dyn Fn(&str) -> *const c_void>;
+
 /// A Vulkan process address
 #[cfg(feature = "vulkan")]
 pub type VkProc = ffi::GLFWvkproc;
@@ -871,6 +981,10 @@ pub type VkProc = ffi::GLFWvkproc;
 /// It uses for "global" refference counting for Glfw.
 static REF_COUNT_FOR_GLFW: AtomicUsize = AtomicUsize::new(0);
 
+/// Held by `EventLoopProxy::send` while it checks that GLFW is initialized and calls into it.
+/// The last `Glfw` takes it once before terminating, to wait for such calls to finish.
+static TERMINATION_LOCK: SpinLock<()> = SpinLock::new(());
+
 /// A struct that represents a thread safe handle to a `Glfw`
 #[derive(Debug)]
 pub struct ThreadSafeGlfw {
@@ -955,6 +1069,36 @@ impl ThreadSafeGlfw {
 
 unsafe impl Send for ThreadSafeGlfw {}
 
+/// A handle that other threads can use to send events of type `T` to the main thread.
+///
+/// Created by `Glfw::create_proxy`. Unlike `ThreadSafeGlfw`, it does not keep GLFW initialized,
+/// so it may outlive the main loop and be dropped on any thread. Events sent after GLFW is
+/// terminated are still queued, but no longer wake the main thread.
+#[derive(Debug)]
+pub struct EventLoopProxy<T> {
+    sender: GlfwSender<T>,
+}
+
+impl<T> EventLoopProxy<T> {
+    /// Queues an event on the receiver created alongside this proxy, then wakes the main thread
+    /// if it is blocked in `wait_events` or `wait_events_timeout`.
+    pub fn send(&self, event: T) {
+        self.sender.send(event);
+        let _lock = TERMINATION_LOCK.lock();
+        if REF_COUNT_FOR_GLFW.load(Ordering::SeqCst) > 0 {
+            unsafe { ffi::glfwPostEmptyEvent() }
+        }
+    }
+}
+
+impl<T> Clone for EventLoopProxy<T> {
+    fn clone(&self) -> Self {
+        EventLoopProxy {
+            sender: self.sender.clone(),
+        }
+    }
+}
+
 /// A token from which to call various GLFW functions. It can be obtained by
 /// calling the `init` function. This cannot be sent to other tasks, and should
 /// only be initialized on the main platform thread. Whilst this might make
@@ -970,7 +1114,8 @@ pub struct Glfw {
 #[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum InitError {
-    /// Deprecated. Does not occur.
+    /// GLFW is already initialized. Not returned by `init`, only by functions that must be
+    /// called before it, such as `init_allocator`.
     AlreadyInitialized,
     /// An internal error occurred when trying to initialize the library.
     Internal,
@@ -989,6 +1134,50 @@ impl fmt::Display for InitError {
 
 impl error::Error for InitError {}
 
+/// An error that might be returned when a window could not be created.
+///
+/// It carries the error that GLFW reported for the failed `glfwCreateWindow` call.
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct WindowCreationError {
+    /// The kind of error reported by GLFW, e.g. `Error::VersionUnavailable`.
+    pub error: Error,
+    /// The human-readable description reported by GLFW.
+    pub description: String,
+}
+
+impl fmt::Display for WindowCreationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Failed to create window: {} ({})",
+            self.description, self.error
+        )
+    }
+}
+
+impl error::Error for WindowCreationError {}
+
+/// An error that might be returned when loading OpenGL functions through a `Context`.
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+pub enum GlLoaderError {
+    /// The context is not current on the calling thread.
+    NotCurrent,
+    /// The context does not provide the function with this name.
+    MissingSymbol(String),
+}
+
+impl fmt::Display for GlLoaderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            GlLoaderError::NotCurrent => f.write_str("Context is not current"),
+            GlLoaderError::MissingSymbol(ref name) => write!(f, "Missing symbol: {}", name),
+        }
+    }
+}
+
+impl error::Error for GlLoaderError {}
+
 /// Initialization hints that can be set using the `init_hint` function.
 #[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
@@ -1007,6 +1196,33 @@ pub enum InitHint {
     ///
     /// This is ignored on platforms besides macOS.
     CocoaMenubar(bool),
+    /// Specifies the platform type, or rendering backend, to request when using OpenGL ES and
+    /// EGL via ANGLE.
+    AnglePlatformType(AnglePlatformType),
+    /// Specifies whether to use libdecor for window decorations where available.
+    ///
+    /// This is ignored on platforms besides Wayland.
+    WaylandLibdecor(bool),
+    /// Specifies whether to prefer the `VK_KHR_xcb_surface` extension over
+    /// `VK_KHR_xlib_surface` for creating Vulkan surfaces.
+    ///
+    /// This is ignored on platforms besides X11.
+    X11XcbVulkanSurface(bool),
+}
+
+/// The ANGLE platform type to request, see [`InitHint::AnglePlatformType`].
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[repr(i32)]
+pub enum AnglePlatformType {
+    /// Lets ANGLE choose.
+    None = ffi::GLFW_ANGLE_PLATFORM_TYPE_NONE,
+    OpenGl = ffi::GLFW_ANGLE_PLATFORM_TYPE_OPENGL,
+    OpenGlEs = ffi::GLFW_ANGLE_PLATFORM_TYPE_OPENGLES,
+    D3D9 = ffi::GLFW_ANGLE_PLATFORM_TYPE_D3D9,
+    D3D11 = ffi::GLFW_ANGLE_PLATFORM_TYPE_D3D11,
+    Vulkan = ffi::GLFW_ANGLE_PLATFORM_TYPE_VULKAN,
+    Metal = ffi::GLFW_ANGLE_PLATFORM_TYPE_METAL,
 }
 
 /// The platform to use when initializing GLFW.
@@ -1056,8 +1272,78 @@ pub fn init_hint(hint: InitHint) {
         InitHint::CocoaMenubar(menubar) => unsafe {
             ffi::glfwInitHint(ffi::GLFW_COCOA_MENUBAR, menubar as c_int)
         },
+        InitHint::AnglePlatformType(platform_type) => unsafe {
+            ffi::glfwInitHint(ffi::GLFW_ANGLE_PLATFORM_TYPE, platform_type as c_int)
+        },
+        InitHint::WaylandLibdecor(libdecor) => unsafe {
+            ffi::glfwInitHint(
+                ffi::GLFW_WAYLAND_LIBDECOR,
+                if libdecor {
+                    ffi::GLFW_WAYLAND_PREFER_LIBDECOR
+                } else {
+                    ffi::GLFW_WAYLAND_DISABLE_LIBDECOR
+                },
+            )
+        },
+        InitHint::X11XcbVulkanSurface(xcb_surface) => unsafe {
+            ffi::glfwInitHint(ffi::GLFW_X11_XCB_VULKAN_SURFACE, xcb_surface as c_int)
+        },
     }
 }
+/// Sets the `vkGetInstanceProcAddr` function GLFW uses for all Vulkan related calls, instead
+/// of loading the Vulkan loader itself. `None` restores the default behavior.
+///
+/// Like `init_hint`, this takes effect the next time GLFW is initialized.
+///
+/// Wrapper for `glfwInitVulkanLoader`.
+///
+/// # Returns
+///
+/// `Err(InitError::AlreadyInitialized)` if GLFW is initialized.
+#[cfg(feature = "vulkan")]
+pub fn init_vulkan_loader(loader: ffi::PFN_vkGetInstanceProcAddr) -> Result<(), InitError> {
+    if REF_COUNT_FOR_GLFW.load(Ordering::SeqCst) > 0 {
+        return Err(InitError::AlreadyInitialized);
+    }
+    unsafe { ffi::glfwInitVulkanLoader(loader) };
+    Ok(())
+}
+
+/// The entry passed to `init_vulkan_loader_from_entry`, which keeps its loader loaded.
+#[cfg(feature = "ash")]
+static VULKAN_ENTRY: SpinLock<Option<ash::Entry>> = SpinLock::new(None);
+
+/// Makes GLFW use the Vulkan loader of an `ash::Entry`, see `init_vulkan_loader`.
+///
+/// A clone of the entry is kept until the next call, so the loader stays loaded.
+///
+/// # Example
+///
+/// ~~~ignore
+/// let entry = unsafe { ash::Entry::load_from("vendor/libvulkan.so.1")? };
+/// glfw::init_vulkan_loader_from_entry(&entry).unwrap();
+/// let mut glfw = glfw::init(glfw::fail_on_errors)?;
+/// ~~~
+#[cfg(feature = "ash")]
+pub fn init_vulkan_loader_from_entry(entry: &ash::Entry) -> Result<(), InitError> {
+    type GlfwGetInstanceProcAddr =
+        unsafe extern "C" fn(ffi::VkInstance, *const c_char) -> ffi::PFN_vkVoidFunction;
+
+    if REF_COUNT_FOR_GLFW.load(Ordering::SeqCst) > 0 {
+        return Err(InitError::AlreadyInitialized);
+    }
+    let mut slot = VULKAN_ENTRY.lock();
+    let entry = slot.insert(entry.clone());
+    // Both are `vkGetInstanceProcAddr`, the handle and function pointer types only differ in
+    // how they are spelled.
+    let loader = unsafe {
+        mem::transmute::<ash::vk::PFN_vkGetInstanceProcAddr, GlfwGetInstanceProcAddr>(
+            entry.static_fn().get_instance_proc_addr,
+        )
+    };
+    init_vulkan_loader(Some(loader))
+}
+
 /// Initializes the GLFW library. This must be called on the main platform
 /// thread.
 ///
@@ -1162,6 +1448,31 @@ impl Glfw {
         callbacks::error::unset();
     }
 
+    /// Runs the closure and collects every error GLFW reports while it runs,
+    /// in the order they were reported.
+    ///
+    /// The errors are not passed to the error callback. The previous callback is
+    /// restored once the closure returns, or if it panics.
+    ///
+    /// # Example
+    ///
+    /// ~~~ignore
+    /// let ((), errors) = glfw.capture_errors(|glfw| {
+    ///     glfw.with_primary_monitor(|_, monitor| {
+    ///         monitor.map(|monitor| monitor.set_gamma(1.5));
+    ///     })
+    /// });
+    /// if let Some((error, description)) = errors.first() {
+    ///     println!("Failed to set gamma: {} ({})", description, error);
+    /// }
+    /// ~~~
+    pub fn capture_errors<T, F>(&mut self, f: F) -> (T, Vec<(Error, String)>)
+    where
+        F: FnOnce(&mut Self) -> T,
+    {
+        callbacks::error::capture(|| f(self))
+    }
+
     /// Sets the monitor callback, overwriting the previous one stored.
     pub fn set_monitor_callback<T>(&mut self, callback: T)
     where
@@ -1269,6 +1580,8 @@ impl Glfw {
     /// `Glfw::create_window`. The hints can be reset to their default values
     /// using calling the `Glfw::default_window_hints` function.
     ///
+    /// To set hints for a single window only, use a `WindowBuilder` instead.
+    ///
     /// Wrapper for `glfwWindowHint`
     ///
     /// # OpenGL 3.x and 4.x on Mac OS X
@@ -1441,6 +1754,21 @@ impl Glfw {
             WindowHint::X11InstanceName(instance_name) => unsafe {
                 string_hint(ffi::GLFW_X11_INSTANCE_NAME, instance_name)
             },
+            WindowHint::PositionX(x) => unsafe {
+                ffi::glfwWindowHint(ffi::GLFW_POSITION_X, unwrap_any_position(x))
+            },
+            WindowHint::PositionY(y) => unsafe {
+                ffi::glfwWindowHint(ffi::GLFW_POSITION_Y, unwrap_any_position(y))
+            },
+            WindowHint::WaylandAppId(app_id) => unsafe {
+                string_hint(ffi::GLFW_WAYLAND_APP_ID, app_id)
+            },
+            WindowHint::Win32KeyboardMenu(keyboard_menu) => unsafe {
+                ffi::glfwWindowHint(ffi::GLFW_WIN32_KEYBOARD_MENU, keyboard_menu as c_int)
+            },
+            WindowHint::Win32ShowDefault(show_default) => unsafe {
+                ffi::glfwWindowHint(ffi::GLFW_WIN32_SHOWDEFAULT, show_default as c_int)
+            },
         }
     }
 
@@ -1454,7 +1782,8 @@ impl Glfw {
         }
     }
 
-    /// Creates a new window.
+    /// Creates a new window using the hints previously set with `Glfw::window_hint`.
+    /// See `WindowBuilder` for creating a window with its own set of hints.
     ///
     /// Wrapper for `glfwCreateWindow`.
     pub fn create_window(
@@ -1472,6 +1801,48 @@ impl Glfw {
         self.create_window_intern(width, height, title, mode, None)
     }
 
+    /// Creates a new window using the hints previously set with `Glfw::window_hint`.
+    ///
+    /// Unlike `Glfw::create_window`, the error reported by GLFW is returned when the
+    /// window could not be created. It is retrieved with `glfwGetError`, so it is
+    /// available regardless of the error callback that is installed.
+    ///
+    /// Wrapper for `glfwCreateWindow`.
+    pub fn try_create_window(
+        &mut self,
+        width: u32,
+        height: u32,
+        title: &str,
+        mode: WindowMode<'_>,
+    ) -> Result<(PWindow, GlfwReceiver<(f64, WindowEvent)>), WindowCreationError> {
+        #[cfg(feature = "wayland")]
+        {
+            // Has to be set otherwise wayland refuses to open window.
+            self.window_hint(WindowHint::Focused(false));
+        }
+        self.try_create_window_intern(width, height, title, mode, None)
+    }
+
+    /// Internal wrapper for `glfwCreateWindow` that reports the error on failure.
+    fn try_create_window_intern(
+        &self,
+        width: u32,
+        height: u32,
+        title: &str,
+        mode: WindowMode<'_>,
+        share: Option<&Window>,
+    ) -> Result<(PWindow, GlfwReceiver<(f64, WindowEvent)>), WindowCreationError> {
+        // Discard any error left over from earlier calls, so that it can not be
+        // mistaken for the reason this creation failed.
+        let _ = take_error();
+        self.create_window_intern(width, height, title, mode, share)
+            .ok_or_else(|| {
+                let (error, description) = take_error()
+                    .unwrap_or((Error::NoError, "no error was reported by GLFW".to_string()));
+                WindowCreationError { error, description }
+            })
+    }
+
     /// Internal wrapper for `glfwCreateWindow`.
     fn create_window_intern(
         &self,
@@ -1607,6 +1978,47 @@ impl Glfw {
         }
     }
 
+    /// Runs an application until it calls `AppContext::exit` or all of its windows have been
+    /// closed.
+    ///
+    /// Events of the windows created through the `AppContext` are dispatched to
+    /// `ApplicationHandler::window_event` with the id of the window they belong to. Joystick and
+    /// monitor events are dispatched as well, which replaces any joystick and monitor callbacks,
+    /// and unsets them when the loop stops. A `MonitorRegistry` therefore stops receiving monitor
+    /// events once the loop starts.
+    ///
+    /// # Example
+    ///
+    /// ~~~ignore
+    /// struct Viewer;
+    ///
+    /// impl glfw::app::ApplicationHandler for Viewer {
+    ///     fn init(&mut self, app: &mut glfw::app::AppContext) {
+    ///         app.create_window(glfw::WindowBuilder::new(640, 480, "viewer")).unwrap();
+    ///     }
+    ///
+    ///     fn window_event(
+    ///         &mut self,
+    ///         app: &mut glfw::app::AppContext,
+    ///         id: glfw::WindowId,
+    ///         _: f64,
+    ///         event: glfw::WindowEvent,
+    ///     ) {
+    ///         if let glfw::WindowEvent::Key(glfw::Key::Escape, _, glfw::Action::Press, _) = event {
+    ///             app.window_mut(id).unwrap().set_should_close(true);
+    ///         }
+    ///     }
+    /// }
+    ///
+    /// glfw.run_app(&mut Viewer);
+    /// ~~~
+    pub fn run_app<H>(&mut self, handler: &mut H)
+    where
+        H: app::ApplicationHandler + ?Sized,
+    {
+        app::run_app(self, handler)
+    }
+
     /// Sleep until at least one event has been received, or until the specified
     /// timeout is reached, and then perform the equivalent of `Glfw::poll_events_unbuffered`.
     /// Timeout is specified in seconds.
@@ -1631,6 +2043,33 @@ impl Glfw {
         }
     }
 
+    /// Creates a proxy for sending events of type `T` to the main thread from other threads,
+    /// along with the receiver those events arrive on.
+    ///
+    /// Each `EventLoopProxy::send` also posts an empty event, so the events can be flushed after
+    /// `wait_events` returns, alongside the events of the application's windows.
+    ///
+    /// # Example
+    ///
+    /// ~~~ignore
+    /// let (proxy, user_events) = glfw.create_proxy::<String>();
+    /// std::thread::spawn(move || proxy.send(load_file()));
+    ///
+    /// while !window.should_close() {
+    ///     glfw.wait_events();
+    ///     for contents in glfw::flush_messages(&user_events) {
+    ///         // handle contents
+    ///     }
+    ///     for (_, event) in glfw::flush_messages(&events) {
+    ///         // handle event
+    ///     }
+    /// }
+    /// ~~~
+    pub fn create_proxy<T: Send>(&self) -> (EventLoopProxy<T>, GlfwReceiver<T>) {
+        let (sender, receiver) = glfw_channel(16, 256);
+        (EventLoopProxy { sender }, receiver)
+    }
+
     /// Returns the current value of the GLFW timer. Unless the timer has been
     /// set using `glfw::set_time`, the timer measures time elapsed since GLFW
     /// was initialized.
@@ -1714,6 +2153,32 @@ impl Glfw {
         None
     }
 
+    /// Like `get_required_instance_extensions`, but borrows the names from GLFW, in the form
+    /// `VkInstanceCreateInfo` expects them.
+    ///
+    /// Returns an empty vector if the API is unavailable.
+    ///
+    /// Wrapper for `glfwGetRequiredInstanceExtensions`.
+    #[cfg(feature = "vulkan")]
+    pub fn required_instance_extensions(&self) -> Vec<&CStr> {
+        let mut len = 0;
+
+        unsafe {
+            let raw_extensions: *const *const c_char =
+                ffi::glfwGetRequiredInstanceExtensions(&mut len);
+
+            if raw_extensions.is_null() {
+                return Vec::new();
+            }
+            // The names stay valid until GLFW is terminated, which cannot happen while `self`
+            // is borrowed.
+            slice::from_raw_parts(raw_extensions, len as usize)
+                .iter()
+                .map(|&extension| CStr::from_ptr(extension))
+                .collect()
+        }
+    }
+
     /// Returns the address of the specified client API or extension function if
     /// it is supported by the current context, NULL otherwise.
     ///
@@ -1814,6 +2279,9 @@ impl Drop for Glfw {
     fn drop(&mut self) {
         let old_diff = REF_COUNT_FOR_GLFW.fetch_sub(1, Ordering::SeqCst);
         if old_diff == 1 {
+            // Proxies that take the lock from now on see GLFW terminated. It is not held while
+            // terminating, as callbacks invoked by `glfwTerminate` may send through a proxy.
+            drop(TERMINATION_LOCK.lock());
             unsafe {
                 ffi::glfwTerminate();
             }
@@ -1825,6 +2293,8 @@ fn glfw_channel<T>(initial_capacity: usize, max_len: usize) -> (GlfwSender<T>, G
     let shared = Arc::new(SharedTransmitter {
         queue: Mutex::new(VecDeque::with_capacity(initial_capacity)),
         max_len,
+        #[cfg(feature = "futures")]
+        waker: Mutex::new(None),
     });
     let (mpsc_sender, mpsc_receiver) = channel();
 
@@ -1843,22 +2313,55 @@ fn glfw_channel<T>(initial_capacity: usize, max_len: usize) -> (GlfwSender<T>, G
 struct SharedTransmitter<T> {
     queue: Mutex<VecDeque<T>>,
     max_len: usize,
+    /// The task waiting on the receiver's `Stream`, if any.
+    #[cfg(feature = "futures")]
+    waker: Mutex<Option<Waker>>,
 }
 
-#[derive(Debug, Clone)]
+impl<T> SharedTransmitter<T> {
+    #[cfg(feature = "futures")]
+    fn wake(&self) {
+        if let Some(waker) = self.waker.lock().unwrap().take() {
+            waker.wake();
+        }
+    }
+}
+
+#[derive(Debug)]
 struct GlfwSender<T> {
     transmitter: Arc<SharedTransmitter<T>>,
     sender: Sender<T>,
 }
 
+impl<T> Clone for GlfwSender<T> {
+    fn clone(&self) -> Self {
+        GlfwSender {
+            transmitter: self.transmitter.clone(),
+            sender: self.sender.clone(),
+        }
+    }
+}
+
 impl<T> GlfwSender<T> {
     fn send(&self, v: T) {
-        let mut queue = self.transmitter.queue.lock().unwrap();
-        if queue.len() >= self.transmitter.max_len {
-            let _ = self.sender.send(v);
-        } else {
-            queue.push_back(v);
+        {
+            let mut queue = self.transmitter.queue.lock().unwrap();
+            if queue.len() >= self.transmitter.max_len {
+                let _ = self.sender.send(v);
+            } else {
+                queue.push_back(v);
+            }
         }
+        #[cfg(feature = "futures")]
+        self.transmitter.wake();
+    }
+}
+
+/// Wakes the receiver's task so that its `Stream` can end once every sender is gone.
+#[cfg(feature = "futures")]
+impl<T> Drop for GlfwSender<T> {
+    fn drop(&mut self) {
+        self.transmitter.wake();
     }
 }
 
@@ -1880,6 +2383,67 @@ impl<T> GlfwReceiver<T> {
             }
         }
     }
+
+    /// Waits for the next message, or returns `None` once every sender is gone, e.g. because
+    /// the window was destroyed.
+    ///
+    /// Messages are only sent while GLFW processes events, so another task must keep calling
+    /// `Glfw::poll_events` or similar for this to complete.
+    ///
+    /// Only available with the `futures` feature.
+    #[cfg(feature = "futures")]
+    pub async fn next_event(&mut self) -> Option<T> {
+        NextEvent { receiver: self }.await
+    }
+}
+
+/// Only available with the `futures` feature.
+///
+/// The stream ends once every sender is gone, e.g. because the window was destroyed.
+///
+/// # Example
+///
+/// ~~~ignore
+/// use futures::StreamExt;
+///
+/// let ui = async move {
+///     while let Some((_, event)) = events.next().await {
+///         // handle event
+///     }
+/// };
+/// ~~~
+#[cfg(feature = "futures")]
+impl<T> futures_core::Stream for GlfwReceiver<T> {
+    type Item = T;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut task::Context<'_>) -> Poll<Option<T>> {
+        let this = self.get_mut();
+        // Register before looking, so that a message sent in between still wakes the task.
+        *this.transmitter.waker.lock().unwrap() = Some(cx.waker().clone());
+        if let Some(message) = this.transmitter.queue.lock().unwrap().pop_front() {
+            return Poll::Ready(Some(message));
+        }
+        match this.receiver.try_recv() {
+            Ok(message) => Poll::Ready(Some(message)),
+            Err(TryRecvError::Empty) => Poll::Pending,
+            Err(TryRecvError::Disconnected) => Poll::Ready(None),
+        }
+    }
+}
+
+/// The future returned by `GlfwReceiver::next_event`.
+#[cfg(feature = "futures")]
+struct NextEvent<'a, T> {
+    receiver: &'a mut GlfwReceiver<T>,
+}
+
+#[cfg(feature = "futures")]
+impl<'a, T> Future for NextEvent<'a, T> {
+    type Output = Option<T>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut task::Context<'_>) -> Poll<Option<T>> {
+        futures_core::Stream::poll_next(Pin::new(&mut *self.get_mut().receiver), cx)
+    }
 }
 
 struct WindowCallbacks {
@@ -1968,6 +2532,21 @@ impl WindowCallbacks {
     }
 }
 
+/// Returns and clears the last error of the calling thread, or `None` if there is
+/// no error.
+fn take_error() -> Option<(Error, String)> {
+    unsafe {
+        let mut description: *const c_char = null();
+        match ffi::glfwGetError(&mut description) {
+            ffi::GLFW_NO_ERROR => None,
+            error => Some((
+                mem::transmute::<c_int, Error>(error),
+                string_from_nullable_c_str(description).unwrap_or_default(),
+            )),
+        }
+    }
+}
+
 /// Wrapper for `glfwGetError`.
 pub fn get_error() -> Error {
     unsafe { mem::transmute(ffi::glfwGetError(null_mut())) }
@@ -2097,28 +2676,27 @@ impl Monitor {
     }
 
     /// Wrapper for `glfwGetGammaRamp`.
+    ///
+    /// # Panics
+    ///
+    /// If the platform does not support gamma ramps, see `try_get_gamma_ramp`.
     pub fn get_gamma_ramp(&self) -> GammaRamp {
-        unsafe {
-            let llramp = *ffi::glfwGetGammaRamp(self.ptr);
-            GammaRamp {
-                red: slice::from_raw_parts(llramp.red as *const c_ushort, llramp.size as usize)
-                    .iter()
-                    .copied()
-                    .collect(),
-                green: slice::from_raw_parts(llramp.green as *const c_ushort, llramp.size as usize)
-                    .iter()
-                    .copied()
-                    .collect(),
-                blue: slice::from_raw_parts(llramp.blue as *const c_ushort, llramp.size as usize)
-                    .iter()
-                    .copied()
-                    .collect(),
-            }
+        match self.try_get_gamma_ramp() {
+            Ok(ramp) => ramp,
+            Err(err) => panic!("{}", err),
         }
     }
 
     /// Wrapper for `glfwSetGammaRamp`.
+    ///
+    /// # Panics
+    ///
+    /// If the channels of the ramp differ in size, see `try_set_gamma_ramp`.
     pub fn set_gamma_ramp(&mut self, ramp: &mut GammaRamp) {
+        assert!(
+            ramp.green.len() == ramp.red.len() && ramp.blue.len() == ramp.red.len(),
+            "gamma ramp channels differ in size"
+        );
         unsafe {
             ffi::glfwSetGammaRamp(
                 self.ptr,
@@ -2317,6 +2895,16 @@ pub enum WindowHint {
     ///
     /// This hint is ignored for full screen windows.
     Maximized(bool),
+    /// Specifies the initial x-coordinate of the content area of a windowed mode window, or
+    /// `None` to let the window manager place it.
+    ///
+    /// This hint is ignored for full screen windows and on Wayland.
+    PositionX(Option<i32>),
+    /// Specifies the initial y-coordinate of the content area of a windowed mode window, or
+    /// `None` to let the window manager place it.
+    ///
+    /// This hint is ignored for full screen windows and on Wayland.
+    PositionY(Option<i32>),
     /// Specifies whether the OpenGL or OpenGL ES contexts do not emit errors,
     /// allowing for better performance in some situations.
     ContextNoError(bool),
@@ -2374,6 +2962,21 @@ pub enum WindowHint {
     X11ClassName(Option<String>),
     /// Specifies the desired ASCII-encoded instance part of the ICCCM `WM_CLASS` window property.
     X11InstanceName(Option<String>),
+    /// Specifies the Wayland `app_id` of the window, which the compositor uses to match it with
+    /// a desktop file.
+    ///
+    /// This is ignored on platforms besides Wayland.
+    WaylandAppId(Option<String>),
+    /// Specifies whether to allow access to the window menu via the Alt+Space and
+    /// Alt-and-then-Space keyboard shortcuts.
+    ///
+    /// This is ignored on platforms besides Windows.
+    Win32KeyboardMenu(bool),
+    /// Specifies whether to show the window the way specified in the `STARTUPINFO` of the
+    /// program when it is shown for the first time.
+    ///
+    /// This is ignored on platforms besides Windows.
+    Win32ShowDefault(bool),
 }
 
 /// Client API tokens.
@@ -2428,6 +3031,122 @@ impl<'a> WindowMode<'a> {
     }
 }
 
+/// Collects everything needed to create a window, so that window hints do not
+/// have to be set on the global `glfwWindowHint` state beforehand.
+///
+/// The hints are reset to their defaults before they are applied, and again
+/// after the window has been created, so they never leak into other window
+/// creations.
+///
+/// # Example
+///
+/// ~~~ignore
+/// let (mut window, events) = glfw::WindowBuilder::new(640, 480, "Tool window")
+///     .hint(glfw::WindowHint::Resizable(false))
+///     .hint(glfw::WindowHint::ContextVersion(3, 3))
+///     .build(&mut glfw)
+///     .expect("Failed to create GLFW window.");
+/// ~~~
+#[derive(Clone, Debug)]
+pub struct WindowBuilder<'a> {
+    width: u32,
+    height: u32,
+    title: String,
+    mode: WindowMode<'a>,
+    share: Option<&'a Window>,
+    hints: Vec<WindowHint>,
+}
+
+impl<'a> WindowBuilder<'a> {
+    /// Creates a builder for a windowed mode window with no hints set.
+    pub fn new(width: u32, height: u32, title: &str) -> WindowBuilder<'a> {
+        WindowBuilder {
+            width,
+            height,
+            title: title.to_string(),
+            mode: WindowMode::Windowed,
+            share: None,
+            hints: Vec::new(),
+        }
+    }
+
+    /// Sets the size of the window.
+    pub fn size(mut self, width: u32, height: u32) -> Self {
+        self.width = width;
+        self.height = height;
+        self
+    }
+
+    /// Sets the initial title of the window.
+    pub fn title(mut self, title: &str) -> Self {
+        self.title = title.to_string();
+        self
+    }
+
+    /// Sets whether the window is created in windowed or full screen mode.
+    pub fn mode(mut self, mode: WindowMode<'a>) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Sets the window whose context the new window will share resources with.
+    pub fn share(mut self, window: &'a Window) -> Self {
+        self.share = Some(window);
+        self
+    }
+
+    /// Adds a window hint. Hints are applied in the order they were added.
+    pub fn hint(mut self, hint: WindowHint) -> Self {
+        self.hints.push(hint);
+        self
+    }
+
+    /// Adds several window hints at once.
+    pub fn hints<I>(mut self, hints: I) -> Self
+    where
+        I: IntoIterator<Item = WindowHint>,
+    {
+        self.hints.extend(hints);
+        self
+    }
+
+    /// Applies the hints, creates the window and restores the default hints.
+    ///
+    /// Wrapper for `glfwCreateWindow`.
+    pub fn build(self, glfw: &mut Glfw) -> Option<(PWindow, GlfwReceiver<(f64, WindowEvent)>)> {
+        self.try_build(glfw).ok()
+    }
+
+    /// Like `WindowBuilder::build`, but returns the error reported by GLFW if the
+    /// window could not be created.
+    ///
+    /// Wrapper for `glfwCreateWindow`.
+    pub fn try_build(
+        self,
+        glfw: &mut Glfw,
+    ) -> Result<(PWindow, GlfwReceiver<(f64, WindowEvent)>), WindowCreationError> {
+        glfw.default_window_hints();
+        #[cfg(feature = "wayland")]
+        {
+            // Has to be set otherwise wayland refuses to open window. It is applied before the
+            // user hints, so an explicit `WindowHint::Focused` still takes precedence.
+            glfw.window_hint(WindowHint::Focused(false));
+        }
+        for hint in self.hints {
+            glfw.window_hint(hint);
+        }
+        let window = glfw.try_create_window_intern(
+            self.width,
+            self.height,
+            &self.title,
+            self.mode,
+            self.share,
+        );
+        glfw.default_window_hints();
+        window
+    }
+}
+
 bitflags! {
     #[doc = "Key modifiers (e.g., Shift, Control, Alt, Super)"]
     #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
@@ -2579,6 +3298,38 @@ impl Window {
         unsafe { ffi::glfwCreateWindowSurface(instance, self.ptr, allocator, surface) }
     }
 
+    /// Creates a Vulkan surface for the window.
+    ///
+    /// The instance must have been created with the extensions returned by
+    /// `Glfw::required_instance_extensions`, and the window with `ClientApiHint::NoApi`. The
+    /// surface has to be destroyed before the window is; `Surface::new` does so automatically.
+    /// Unlike `Surface`, the returned handle does not borrow the window, so the window can still
+    /// be mutated while it exists.
+    ///
+    /// Wrapper for `glfwCreateWindowSurface`.
+    #[cfg(feature = "ash")]
+    pub fn create_surface(
+        &self,
+        instance: &ash::Instance,
+    ) -> Result<ash::vk::SurfaceKHR, ash::vk::Result> {
+        use ash::vk::{self, Handle};
+
+        // `VkSurfaceKHR` is a 64-bit handle on every platform, whatever the bindings say.
+        let mut surface = vk::SurfaceKHR::null();
+        let result = unsafe {
+            ffi::glfwCreateWindowSurface(
+                instance.handle().as_raw() as ffi::VkInstance,
+                self.ptr,
+                ptr::null(),
+                &mut surface as *mut vk::SurfaceKHR as *mut ffi::VkSurfaceKHR,
+            )
+        };
+        match vk::Result::from_raw(result) {
+            vk::Result::SUCCESS => Ok(surface),
+            err => Err(err),
+        }
+    }
+
     /// Creates a new shared window.
     ///
     /// Wrapper for `glfwCreateWindow`.
@@ -2593,6 +3344,21 @@ impl Window {
             .create_window_intern(width, height, title, mode, Some(self))
     }
 
+    /// Creates a new shared window, returning the error reported by GLFW on failure.
+    /// See `Glfw::try_create_window`.
+    ///
+    /// Wrapper for `glfwCreateWindow`.
+    pub fn try_create_shared(
+        &self,
+        width: u32,
+        height: u32,
+        title: &str,
+        mode: WindowMode<'_>,
+    ) -> Result<(PWindow, GlfwReceiver<(f64, WindowEvent)>), WindowCreationError> {
+        self.glfw
+            .try_create_window_intern(width, height, title, mode, Some(self))
+    }
+
     /// Calling this method forces the destructor to be called, closing the
     /// window.
     pub fn close(self) {}
@@ -2618,6 +3384,13 @@ impl Window {
         unsafe { ffi::glfwSetWindowShouldClose(self.ptr, value as c_int) }
     }
 
+    /// Returns the title of the window, as last set by `create_window` or `set_title`.
+    ///
+    /// Wrapper for `glfwGetWindowTitle`.
+    pub fn get_title(&self) -> String {
+        unsafe { string_from_nullable_c_str(ffi::glfwGetWindowTitle(self.ptr)).unwrap_or_default() }
+    }
+
     /// Sets the title of the window.
     ///
     /// Wrapper for `glfwSetWindowTitle`.
@@ -2629,6 +3402,21 @@ impl Window {
         }
     }
 
+    /// Returns an adapter whose methods report the error GLFW raised during the
+    /// call, instead of only passing it to the error callback.
+    ///
+    /// # Example
+    ///
+    /// ~~~ignore
+    /// // Wayland does not allow windows to position themselves.
+    /// if let Err(glfw::Error::FeatureUnavailable) = window.checked().set_pos(100, 100) {
+    ///     println!("window positioning is not supported on this platform");
+    /// }
+    /// ~~~
+    pub fn checked(&mut self) -> CheckedWindow<'_> {
+        CheckedWindow { window: self }
+    }
+
     /// Wrapper for `glfwGetWindowPos`.
     pub fn get_pos(&self) -> (i32, i32) {
         unsafe {
@@ -3218,6 +4006,97 @@ impl Window {
         self.set_content_scale_polling(should_poll);
     }
 
+    /// Dispatches an event as if GLFW had reported it for this window.
+    ///
+    /// The event takes the same path as a real one: the user callback set for it is called,
+    /// then, if polling is enabled for it, the event is timestamped with `glfwGetTime`, offered
+    /// to the `poll_events_unbuffered` handler and sent to the window's receiver.
+    ///
+    /// Nothing is changed on the GLFW side, e.g. injecting `WindowEvent::Size` does not resize
+    /// the window.
+    ///
+    /// Only available with the `testing` or `recording` feature.
+    ///
+    /// # Example
+    ///
+    /// ~~~ignore
+    /// window.set_key_polling(true);
+    /// window.inject_event(glfw::WindowEvent::Key(
+    ///     glfw::Key::Escape, 0, glfw::Action::Press, glfw::Modifiers::empty(),
+    /// ));
+    /// ~~~
+    #[cfg(any(feature = "testing", feature = "recording"))]
+    pub fn inject_event(&mut self, event: WindowEvent) {
+        let callbacks = WindowCallbacks::get_callbacks(self.ptr);
+
+        macro_rules! dispatch {
+            ($callback_field:ident, $poll_field:ident $(, $args:expr)*) => {{
+                if let Some(func) = &mut callbacks.$callback_field {
+                    func(self, $($args),*);
+                }
+                callbacks.$poll_field
+            }};
+        }
+
+        let should_poll = match event {
+            WindowEvent::Pos(x, y) => dispatch!(pos_callback, pos_polling, x, y),
+            WindowEvent::Size(width, height) => {
+                dispatch!(size_callback, size_polling, width, height)
+            }
+            WindowEvent::Close => dispatch!(close_callback, close_polling),
+            WindowEvent::Refresh => dispatch!(refresh_callback, refresh_polling),
+            WindowEvent::Focus(focused) => dispatch!(focus_callback, focus_polling, focused),
+            WindowEvent::Iconify(iconified) => {
+                dispatch!(iconify_callback, iconify_polling, iconified)
+            }
+            WindowEvent::FramebufferSize(width, height) => dispatch!(
+                framebuffer_size_callback,
+                framebuffer_size_polling,
+                width,
+                height
+            ),
+            WindowEvent::MouseButton(button, action, mods) => dispatch!(
+                mouse_button_callback,
+                mouse_button_polling,
+                button,
+                action,
+                mods
+            ),
+            WindowEvent::CursorPos(x, y) => {
+                dispatch!(cursor_pos_callback, cursor_pos_polling, x, y)
+            }
+            WindowEvent::CursorEnter(entered) => {
+                dispatch!(cursor_enter_callback, cursor_enter_polling, entered)
+            }
+            WindowEvent::Scroll(x, y) => dispatch!(scroll_callback, scroll_polling, x, y),
+            WindowEvent::Key(key, scancode, action, mods) => {
+                dispatch!(key_callback, key_polling, key, scancode, action, mods)
+            }
+            WindowEvent::Char(character) => dispatch!(char_callback, char_polling, character),
+            WindowEvent::CharModifiers(character, mods) => {
+                dispatch!(char_mods_callback, char_mods_polling, character, mods)
+            }
+            WindowEvent::FileDrop(ref paths) => {
+                dispatch!(drag_and_drop_callback, drag_and_drop_polling, paths.clone())
+            }
+            WindowEvent::Maximize(maximized) => {
+                dispatch!(maximize_callback, maximize_polling, maximized)
+            }
+            WindowEvent::ContentScale(x, y) => {
+                dispatch!(content_scale_callback, content_scale_polling, x, y)
+            }
+        };
+
+        if should_poll {
+            let event = (unsafe { ffi::glfwGetTime() } as f64, event);
+            if let Some(event) =
+                unsafe { callbacks::unbuffered::handle(self.ptr as WindowId, event) }
+            {
+                callbacks.sender.send(event);
+            }
+        }
+    }
+
     /// Wrapper for `glfwGetInputMode` called with `CURSOR`.
     pub fn get_cursor_mode(&self) -> CursorMode {
         unsafe { mem::transmute(ffi::glfwGetInputMode(self.ptr, ffi::GLFW_CURSOR)) }
@@ -3477,6 +4356,91 @@ impl Window {
     }
 }
 
+/// Runs `f` and returns the last error GLFW reported during the call, if any.
+fn check_error<T, F>(f: F) -> Result<T, Error>
+where
+    F: FnOnce() -> T,
+{
+    // Discard errors from earlier calls so they are not attributed to this one.
+    let _ = take_error();
+    let value = f();
+    match take_error() {
+        Some((error, _)) => Err(error),
+        None => Ok(value),
+    }
+}
+
+/// A `Window` adapter, obtained with `Window::checked`, whose methods return the
+/// error reported by GLFW.
+///
+/// After each call `glfwGetError` is checked, and the last error that occurred
+/// during the call is returned as `Err`. Errors are still passed to the error
+/// callback as usual, so it should not be one that panics, like `fail_on_errors`.
+#[derive(Debug)]
+pub struct CheckedWindow<'a> {
+    window: &'a mut Window,
+}
+
+impl<'a> CheckedWindow<'a> {
+    checked_methods! {
+        fn get_title() -> String;
+        fn set_title(title: &str) -> ();
+        fn get_pos() -> (i32, i32);
+        fn set_pos(xpos: i32, ypos: i32) -> ();
+        fn get_size() -> (i32, i32);
+        fn set_size(width: i32, height: i32) -> ();
+        fn get_frame_size() -> (i32, i32, i32, i32);
+        fn get_framebuffer_size() -> (i32, i32);
+        fn set_aspect_ratio(numer: u32, denum: u32) -> ();
+        fn set_size_limits(
+            minwidth: Option<u32>,
+            minheight: Option<u32>,
+            maxwidth: Option<u32>,
+            maxheight: Option<u32>
+        ) -> ();
+        fn iconify() -> ();
+        fn restore() -> ();
+        fn maximize() -> ();
+        fn show() -> ();
+        fn hide() -> ();
+        fn set_monitor(
+            mode: WindowMode<'_>,
+            xpos: i32,
+            ypos: i32,
+            width: u32,
+            height: u32,
+            refresh_rate: Option<u32>
+        ) -> ();
+        fn focus() -> ();
+        fn set_resizable(resizable: bool) -> ();
+        fn set_decorated(decorated: bool) -> ();
+        fn set_auto_iconify(auto_iconify: bool) -> ();
+        fn set_floating(floating: bool) -> ();
+        fn set_mouse_passthrough(passthrough: bool) -> ();
+        fn set_focus_on_show(focus_on_show: bool) -> ();
+        fn get_cursor_mode() -> CursorMode;
+        fn set_cursor_mode(mode: CursorMode) -> ();
+        fn set_cursor(cursor: Option<Cursor>) -> Option<Cursor>;
+        #[cfg(feature = "image")]
+        fn set_icon(images: Vec<image::RgbaImage>) -> ();
+        fn set_icon_from_pixels(images: Vec<PixelImage>) -> ();
+        fn set_sticky_keys(value: bool) -> ();
+        fn set_sticky_mouse_buttons(value: bool) -> ();
+        fn set_store_lock_key_mods(value: bool) -> ();
+        fn set_raw_mouse_motion(value: bool) -> ();
+        fn get_key(key: Key) -> Action;
+        fn get_mouse_button(button: MouseButton) -> Action;
+        fn get_cursor_pos() -> (f64, f64);
+        fn set_cursor_pos(xpos: f64, ypos: f64) -> ();
+        fn set_clipboard_string(string: &str) -> ();
+        fn get_clipboard_string() -> Option<String>;
+        fn get_opacity() -> f32;
+        fn set_opacity(opacity: f32) -> ();
+        fn request_attention() -> ();
+        fn get_content_scale() -> (f32, f32);
+    }
+}
+
 impl Drop for Window {
     /// Closes the window and performs the necessary cleanups. This will block
     /// until all associated `RenderContext`s were also dropped, and emit a
@@ -3509,6 +4473,72 @@ impl Drop for Window {
     }
 }
 
+/// A Vulkan surface for a window, destroyed when dropped.
+///
+/// The surface borrows the window, so that it is always destroyed first. The instance it was
+/// created with must outlive it. Applications that need to mutate the window while the surface
+/// exists can use `Window::create_surface` and destroy the surface themselves.
+///
+/// # Example
+///
+/// ~~~ignore
+/// let surface = glfw::Surface::new(&window, &entry, &instance)?;
+/// let caps = unsafe {
+///     surface
+///         .loader()
+///         .get_physical_device_surface_capabilities(physical_device, surface.handle())?
+/// };
+/// ~~~
+#[cfg(feature = "ash")]
+pub struct Surface<'a> {
+    handle: ash::vk::SurfaceKHR,
+    loader: ash::khr::surface::Instance,
+    _window: std::marker::PhantomData<&'a Window>,
+}
+
+#[cfg(feature = "ash")]
+impl<'a> Surface<'a> {
+    /// Creates a surface for `window` with `Window::create_surface`.
+    pub fn new(
+        window: &'a Window,
+        entry: &ash::Entry,
+        instance: &ash::Instance,
+    ) -> Result<Surface<'a>, ash::vk::Result> {
+        Ok(Surface {
+            handle: window.create_surface(instance)?,
+            loader: ash::khr::surface::Instance::new(entry, instance),
+            _window: std::marker::PhantomData,
+        })
+    }
+
+    /// The raw surface handle.
+    pub fn handle(&self) -> ash::vk::SurfaceKHR {
+        self.handle
+    }
+
+    /// The `VK_KHR_surface` functions, for querying the surface.
+    pub fn loader(&self) -> &ash::khr::surface::Instance {
+        &self.loader
+    }
+}
+
+#[cfg(feature = "ash")]
+impl<'a> fmt::Debug for Surface<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Surface")
+            .field("handle", &self.handle)
+            .finish()
+    }
+}
+
+#[cfg(feature = "ash")]
+impl<'a> Drop for Surface<'a> {
+    /// Wrapper for `vkDestroySurfaceKHR`.
+    fn drop(&mut self) {
+        unsafe { self.loader.destroy_surface(self.handle, None) }
+    }
+}
+
 #[derive(Debug)]
 #[repr(transparent)]
 pub struct PRenderContext(Box<RenderContext>);
@@ -3635,6 +4665,91 @@ pub trait Context {
         }
     }
 
+    /// Makes the context current until the returned guard is dropped, at which
+    /// point the context that was current before is made current again.
+    ///
+    /// The window whose context was current before must not be destroyed while
+    /// the guard exists.
+    ///
+    /// # Example
+    ///
+    /// ~~~ignore
+    /// {
+    ///     let _guard = loading_window.make_current_scoped();
+    ///     upload_textures();
+    /// }
+    /// // The previous context is current again.
+    /// ~~~
+    fn make_current_scoped(&mut self) -> CurrentContextGuard<'_> {
+        let previous = unsafe { ffi::glfwGetCurrentContext() };
+        self.make_current();
+        CurrentContextGuard {
+            previous,
+            _context: std::marker::PhantomData,
+        }
+    }
+
+    /// Returns the address of an OpenGL function of this context.
+    ///
+    /// Unlike `Window::get_proc_address`, this does not make the context
+    /// current, but reports an error if it is not.
+    ///
+    /// Wrapper for `glfwGetProcAddress`.
+    fn proc_address(&self, procname: &str) -> Result<*const c_void, GlLoaderError> {
+        if !self.is_current() {
+            return Err(GlLoaderError::NotCurrent);
+        }
+        match with_c_str(procname, |procname| unsafe {
+            ffi::glfwGetProcAddress(procname)
+        }) {
+            Some(proc) => Ok(proc as *const c_void),
+            None => Err(GlLoaderError::MissingSymbol(procname.to_string())),
+        }
+    }
+
+    /// Returns a function loader for crates such as `gl`, whose `load_with`
+    /// expects a null pointer for missing functions.
+    ///
+    /// The loader returns a null pointer as well if the context is no longer
+    /// current when it is called.
+    ///
+    /// # Example
+    ///
+    /// ~~~ignore
+    /// window.make_current();
+    /// gl::load_with(window.gl_loader()?);
+    /// ~~~
+    fn gl_loader(&self) -> Result<GlLoader, GlLoaderError> {
+        if !self.is_current() {
+            return Err(GlLoaderError::NotCurrent);
+        }
+        let ptr = self.window_ptr();
+        Ok(Box::new(move |procname| {
+            if ptr != unsafe { ffi::glfwGetCurrentContext() } {
+                return ptr::null();
+            }
+            with_c_str(procname, |procname| unsafe {
+                ffi::glfwGetProcAddress(procname)
+            })
+            .map_or(ptr::null(), |proc| proc as *const c_void)
+        }))
+    }
+
+    /// Loads the OpenGL functions of this context into a `glow::Context`.
+    ///
+    /// The returned context must only be used while this one is current.
+    #[cfg(feature = "glow")]
+    fn glow_context(&self) -> Result<glow::Context, GlLoaderError> {
+        // glow queries the version while loading, and cannot cope without it.
+        self.proc_address("glGetString")?;
+        Ok(unsafe {
+            glow::Context::from_loader_function_cstr(|procname| {
+                ffi::glfwGetProcAddress(procname.as_ptr())
+                    .map_or(ptr::null(), |proc| proc as *const c_void)
+            })
+        })
+    }
+
     /// Wrapper for `glfwWindowShouldClose`.
     fn should_close(&self) -> bool {
         let ptr = self.window_ptr();
@@ -3655,6 +4770,23 @@ pub trait Context {
     }
 }
 
+/// Restores the previously current context when dropped.
+///
+/// Returned by `Context::make_current_scoped`. The current context is a
+/// property of the thread, so the guard cannot be sent to another one.
+#[derive(Debug)]
+pub struct CurrentContextGuard<'a> {
+    previous: *mut ffi::GLFWwindow,
+    _context: std::marker::PhantomData<&'a mut ()>,
+}
+
+impl<'a> Drop for CurrentContextGuard<'a> {
+    /// Wrapper for `glfwMakeContextCurrent`.
+    fn drop(&mut self) {
+        unsafe { ffi::glfwMakeContextCurrent(self.previous) }
+    }
+}
+
 impl Context for Window {
     fn window_ptr(&self) -> *mut ffi::GLFWwindow {
         self.ptr
@@ -4190,3 +5322,11 @@ fn unwrap_dont_care(value: Option<u32>) -> c_int {
         None => ffi::GLFW_DONT_CARE,
     }
 }
+
+#[inline(always)]
+fn unwrap_any_position(value: Option<i32>) -> c_int {
+    match value {
+        Some(v) => v as c_int,
+        None => ffi::GLFW_ANY_POSITION as c_int,
+    }
+}
@@ -0,0 +1,282 @@
+// Copyright 2013 The GLFW-RS Developers. For a full listing of the authors,
+// refer to the AUTHORS file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Tracking of connected monitors outside of the `with_*_monitor` closures.
+
+use std::cell::{RefCell, RefMut};
+use std::rc::{Rc, Weak};
+use std::{error, fmt, slice};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::{ffi, Glfw, Monitor, MonitorEvent, VidMode};
+
+/// Identifies a monitor tracked by a `MonitorRegistry`.
+///
+/// Unlike monitor handles, ids are never reused, so an id of a disconnected monitor cannot
+/// refer to a monitor connected later.
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct MonitorId(u64);
+
+/// The properties of a monitor at the time it was captured.
+#[derive(Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct MonitorInfo {
+    pub id: MonitorId,
+    /// See `Monitor::get_name`.
+    pub name: Option<String>,
+    /// The position of the monitor on the virtual desktop, in screen coordinates.
+    pub pos: (i32, i32),
+    /// The size of the display area in millimetres.
+    pub physical_size: (i32, i32),
+    /// The area not occupied by task bars and the like, as `(x, y, width, height)`.
+    pub workarea: (i32, i32, i32, i32),
+    /// See `Monitor::get_content_scale`.
+    pub content_scale: (f32, f32),
+    /// The current video mode.
+    pub video_mode: Option<VidMode>,
+}
+
+impl MonitorInfo {
+    fn capture(id: MonitorId, monitor: &Monitor) -> MonitorInfo {
+        MonitorInfo {
+            id,
+            name: monitor.get_name(),
+            pos: monitor.get_pos(),
+            physical_size: monitor.get_physical_size(),
+            workarea: monitor.get_workarea(),
+            content_scale: monitor.get_content_scale(),
+            video_mode: monitor.get_video_mode(),
+        }
+    }
+
+    /// Captures monitors outside of a registry, with ids that are only unique among them.
+    pub(crate) fn capture_all(monitors: &[&mut Monitor]) -> Vec<MonitorInfo> {
+        monitors
+            .iter()
+            .enumerate()
+            .map(|(index, monitor)| MonitorInfo::capture(MonitorId(index as u64), monitor))
+            .collect()
+    }
+}
+
+/// An error returned when looking up a monitor in a `MonitorRegistry`.
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum MonitorError {
+    /// The monitor has been disconnected, or was never tracked by this registry.
+    Disconnected(MonitorId),
+}
+
+impl fmt::Display for MonitorError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            MonitorError::Disconnected(id) => write!(f, "Monitor {:?} is disconnected", id),
+        }
+    }
+}
+
+impl error::Error for MonitorError {}
+
+#[derive(Debug)]
+struct Entry {
+    ptr: *mut ffi::GLFWmonitor,
+    info: MonitorInfo,
+}
+
+#[derive(Debug, Default)]
+struct RegistryState {
+    next_id: u64,
+    entries: Vec<Entry>,
+    events: Vec<(MonitorId, MonitorEvent)>,
+}
+
+impl RegistryState {
+    fn connect(&mut self, monitor: &Monitor) -> MonitorId {
+        let id = MonitorId(self.next_id);
+        self.next_id += 1;
+        self.entries.push(Entry {
+            ptr: monitor.ptr,
+            info: MonitorInfo::capture(id, monitor),
+        });
+        id
+    }
+
+    fn disconnect(&mut self, monitor: &Monitor) -> Option<MonitorId> {
+        let index = self
+            .entries
+            .iter()
+            .position(|entry| entry.ptr == monitor.ptr)?;
+        Some(self.entries.remove(index).info.id)
+    }
+
+    /// Forgets the monitors that are no longer connected, whose handles are no longer valid.
+    ///
+    /// The callback normally does so as they are disconnected, but it stops being called once
+    /// another monitor callback replaces it.
+    fn prune(&mut self) {
+        let connected = unsafe {
+            let mut count = 0;
+            let ptr = ffi::glfwGetMonitors(&mut count);
+            if ptr.is_null() {
+                &[][..]
+            } else {
+                slice::from_raw_parts(ptr as *const *mut ffi::GLFWmonitor, count as usize)
+            }
+        };
+        let events = &mut self.events;
+        self.entries.retain(|entry| {
+            let is_connected = connected.contains(&entry.ptr);
+            if !is_connected {
+                events.push((entry.info.id, MonitorEvent::Disconnected));
+            }
+            is_connected
+        });
+    }
+
+    fn entry(&self, id: MonitorId) -> Result<&Entry, MonitorError> {
+        self.entries
+            .iter()
+            .find(|entry| entry.info.id == id)
+            .ok_or(MonitorError::Disconnected(id))
+    }
+}
+
+/// Keeps track of the connected monitors, and caches their properties.
+///
+/// The registry sets the monitor callback, replacing any that was set before, and stops
+/// tracking changes once it is dropped. Monitor events can be retrieved with `take_events`
+/// instead.
+///
+/// Properties such as the video mode can change without a monitor event. `refresh` captures
+/// them again.
+///
+/// Only one monitor callback can be set at a time, so the registry cannot be used alongside
+/// `Glfw::run_app` or `EventRecorder::record_monitor_events`, which set their own. If its
+/// callback is replaced, the registry still notices disconnected monitors whenever it is
+/// queried, but no longer notices connected ones.
+///
+/// # Example
+///
+/// ~~~ignore
+/// let registry = glfw::MonitorRegistry::new(&mut glfw);
+/// let primary = registry.primary().unwrap();
+/// // ...
+/// glfw.poll_events();
+/// match registry.info(primary.id) {
+///     Ok(info) => println!("still connected: {:?}", info.name),
+///     Err(err) => println!("{}", err),
+/// }
+/// ~~~
+#[derive(Debug)]
+pub struct MonitorRegistry {
+    state: Rc<RefCell<RegistryState>>,
+    /// Keeps the library, and with it the monitor handles, alive.
+    _glfw: Glfw,
+}
+
+impl MonitorRegistry {
+    /// Captures the connected monitors and starts tracking monitor events.
+    pub fn new(glfw: &mut Glfw) -> MonitorRegistry {
+        let state = Rc::new(RefCell::new(RegistryState::default()));
+        glfw.with_connected_monitors(|_, monitors| {
+            let mut state = state.borrow_mut();
+            for monitor in monitors {
+                state.connect(monitor);
+            }
+        });
+
+        let weak: Weak<RefCell<RegistryState>> = Rc::downgrade(&state);
+        glfw.set_monitor_callback(move |monitor, event| {
+            // The monitor is only valid during the callback, so it is captured right away.
+            if let Some(state) = weak.upgrade() {
+                let mut state = state.borrow_mut();
+                let id = match event {
+                    MonitorEvent::Connected => Some(state.connect(&monitor)),
+                    MonitorEvent::Disconnected => state.disconnect(&monitor),
+                };
+                if let Some(id) = id {
+                    state.events.push((id, event));
+                }
+            }
+        });
+
+        MonitorRegistry {
+            state,
+            _glfw: glfw.clone(),
+        }
+    }
+
+    /// The state, without the monitors that have been disconnected.
+    fn state(&self) -> RefMut<'_, RegistryState> {
+        let mut state = self.state.borrow_mut();
+        state.prune();
+        state
+    }
+
+    /// The connected monitors, in the order they were connected.
+    pub fn monitors(&self) -> Vec<MonitorInfo> {
+        self.state()
+            .entries
+            .iter()
+            .map(|entry| entry.info.clone())
+            .collect()
+    }
+
+    /// The primary monitor, if any is connected.
+    pub fn primary(&self) -> Option<MonitorInfo> {
+        let primary = unsafe { ffi::glfwGetPrimaryMonitor() };
+        self.state()
+            .entries
+            .iter()
+            .find(|entry| entry.ptr == primary)
+            .map(|entry| entry.info.clone())
+    }
+
+    /// The properties of a monitor, as last captured.
+    pub fn info(&self, id: MonitorId) -> Result<MonitorInfo, MonitorError> {
+        self.state().entry(id).map(|entry| entry.info.clone())
+    }
+
+    /// Whether the monitor is still connected.
+    pub fn is_connected(&self, id: MonitorId) -> bool {
+        self.state().entry(id).is_ok()
+    }
+
+    /// Supplies the monitor to the closure provided, for functions that need a live handle such
+    /// as `Window::set_monitor`.
+    pub fn with_monitor<T, F>(&self, id: MonitorId, f: F) -> Result<T, MonitorError>
+    where
+        F: FnOnce(&mut Monitor) -> T,
+    {
+        let ptr = self.state().entry(id)?.ptr;
+        Ok(f(&mut Monitor { ptr }))
+    }
+
+    /// Captures the properties of every connected monitor again.
+    pub fn refresh(&self) {
+        let mut state = self.state();
+        for entry in &mut state.entries {
+            entry.info = MonitorInfo::capture(entry.info.id, &Monitor { ptr: entry.ptr });
+        }
+    }
+
+    /// Returns the monitors connected and disconnected since the last call, in order.
+    pub fn take_events(&self) -> Vec<(MonitorId, MonitorEvent)> {
+        std::mem::take(&mut self.state().events)
+    }
+}
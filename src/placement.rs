@@ -0,0 +1,232 @@
+// Copyright 2013 The GLFW-RS Developers. For a full listing of the authors,
+// refer to the AUTHORS file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Saving and restoring where a window is on the desktop.
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::desktop::intersection_area;
+use crate::{DesktopLayout, MonitorInfo, VidMode, VideoModeQuery, Window, WindowMode};
+
+/// The fullscreen state of a window, as part of a `WindowPlacement`.
+#[derive(Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct FullscreenPlacement {
+    /// The name of the monitor the window was fullscreen on.
+    pub monitor: Option<String>,
+    /// The video mode of that monitor.
+    pub video_mode: VidMode,
+}
+
+/// The position, size and state of a window, captured with `Window::placement` so that it can
+/// be restored with `Window::restore_placement`, possibly in a later session.
+///
+/// GLFW does not report the geometry a maximized window had before it was maximized, so the
+/// placement of a maximized window holds the maximized geometry. A window restored from it is
+/// maximized again, but keeps the maximized size if the user restores it afterwards.
+///
+/// # Example
+///
+/// ~~~ignore
+/// // on exit
+/// std::fs::write("window.json", serde_json::to_string(&window.placement())?)?;
+///
+/// // on start up
+/// if let Ok(json) = std::fs::read_to_string("window.json") {
+///     window.restore_placement(&serde_json::from_str(&json)?);
+/// }
+/// ~~~
+#[derive(Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct WindowPlacement {
+    /// The position of the content area, in screen coordinates. For maximized windows, this is
+    /// the maximized position.
+    pub pos: (i32, i32),
+    /// The size of the content area, in screen coordinates. For maximized windows, this is the
+    /// maximized size.
+    pub size: (i32, i32),
+    pub maximized: bool,
+    pub iconified: bool,
+    /// Set if the window was fullscreen.
+    pub fullscreen: Option<FullscreenPlacement>,
+    /// The name of the monitor the window overlapped the most, or was fullscreen on.
+    pub monitor: Option<String>,
+    /// The content scale of the window.
+    pub content_scale: (f32, f32),
+}
+
+/// Scales a length by the ratio of two content scales, if the original one is known.
+fn rescale(len: i32, from: f32, to: f32) -> i32 {
+    if from > 0.0 && to > 0.0 {
+        (len as f32 * to / from).round() as i32
+    } else {
+        len
+    }
+}
+
+impl Window {
+    /// Captures the position, size and state of the window.
+    ///
+    /// See `WindowPlacement` for what is captured of maximized windows.
+    pub fn placement(&self) -> WindowPlacement {
+        let (x, y) = self.get_pos();
+        let (width, height) = self.get_size();
+        let fullscreen = self.with_window_mode(|mode| match mode {
+            WindowMode::FullScreen(monitor) => {
+                monitor
+                    .get_video_mode()
+                    .map(|video_mode| FullscreenPlacement {
+                        monitor: monitor.get_name(),
+                        video_mode,
+                    })
+            }
+            WindowMode::Windowed => None,
+        });
+        let monitor = match fullscreen {
+            Some(ref fullscreen) => fullscreen.monitor.clone(),
+            None => self.glfw.clone().with_connected_monitors(|_, monitors| {
+                DesktopLayout::new(MonitorInfo::capture_all(monitors))
+                    .monitor_for_rect((x, y, width, height))
+                    .and_then(|info| info.name.clone())
+            }),
+        };
+        WindowPlacement {
+            pos: (x, y),
+            size: (width, height),
+            maximized: self.is_maximized(),
+            iconified: self.is_iconified(),
+            fullscreen,
+            monitor,
+            content_scale: self.get_content_scale(),
+        }
+    }
+
+    /// Moves, resizes and maximizes, iconifies or makes the window fullscreen as described by
+    /// `placement`.
+    ///
+    /// The placement is only applied as saved if its monitor is still connected, and, for
+    /// windowed placements, if the window would overlap it. Monitors are matched by name.
+    /// Otherwise the window is centered on the workarea of the primary monitor, with its size
+    /// adjusted for the content scale of that monitor and shrunk to fit. Fullscreen windows
+    /// use the mode of the monitor closest to the saved one.
+    ///
+    /// # Returns
+    ///
+    /// `false` if the window was placed on the primary monitor instead.
+    pub fn restore_placement(&mut self, placement: &WindowPlacement) -> bool {
+        if self.is_iconified() || self.is_maximized() {
+            self.restore();
+        }
+
+        let mut glfw = self.glfw.clone();
+        let restored = glfw.with_connected_monitors(|_, monitors| {
+            let layout = DesktopLayout::new(MonitorInfo::capture_all(monitors));
+            // The layout captures the monitors in order, so indices are shared.
+            let find = |name: &Option<String>| {
+                name.as_ref().and_then(|name| {
+                    layout
+                        .monitors()
+                        .iter()
+                        .position(|info| info.name.as_ref() == Some(name))
+                })
+            };
+
+            match placement.fullscreen {
+                Some(ref fullscreen) => {
+                    let monitor = match find(&fullscreen.monitor) {
+                        Some(index) => &*monitors[index],
+                        None => return false,
+                    };
+                    let saved = fullscreen.video_mode;
+                    let query = VideoModeQuery::new()
+                        .exact_resolution(saved.width, saved.height)
+                        .refresh_rate(saved.refresh_rate);
+                    match monitor
+                        .best_video_mode(&query)
+                        .or_else(|| monitor.get_video_mode())
+                    {
+                        Some(mode) => {
+                            self.set_monitor(
+                                WindowMode::FullScreen(monitor),
+                                0,
+                                0,
+                                mode.width,
+                                mode.height,
+                                Some(mode.refresh_rate),
+                            );
+                            true
+                        }
+                        None => false,
+                    }
+                }
+                None => {
+                    let (x, y) = placement.pos;
+                    let (width, height) = placement.size;
+                    if width <= 0 || height <= 0 {
+                        return false;
+                    }
+                    let rect = (x, y, width, height);
+                    let on_screen = match placement.monitor {
+                        Some(_) => find(&placement.monitor).map_or(false, |index| {
+                            intersection_area(layout.monitors()[index].bounds(), rect) > 0
+                        }),
+                        None => layout.monitor_for_rect(rect).is_some(),
+                    };
+                    if on_screen {
+                        self.set_monitor(
+                            WindowMode::Windowed,
+                            x,
+                            y,
+                            width as u32,
+                            height as u32,
+                            None,
+                        );
+                    }
+                    on_screen
+                }
+            }
+        });
+
+        if !restored {
+            glfw.with_primary_monitor(|_, monitor| {
+                if let Some(monitor) = monitor {
+                    let (wx, wy, wwidth, wheight) = monitor.get_workarea();
+                    let (xscale, yscale) = monitor.get_content_scale();
+                    let (width, height) = placement.size;
+                    let width =
+                        rescale(width, placement.content_scale.0, xscale).clamp(1, wwidth.max(1));
+                    let height =
+                        rescale(height, placement.content_scale.1, yscale).clamp(1, wheight.max(1));
+                    self.set_monitor(
+                        WindowMode::Windowed,
+                        wx + (wwidth - width) / 2,
+                        wy + (wheight - height) / 2,
+                        width as u32,
+                        height as u32,
+                        None,
+                    );
+                }
+            });
+        }
+
+        if placement.maximized {
+            self.maximize();
+        } else if placement.iconified {
+            self.iconify();
+        }
+        restored
+    }
+}
@@ -0,0 +1,301 @@
+// Copyright 2013 The GLFW-RS Developers. For a full listing of the authors,
+// refer to the AUTHORS file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Recording and replay of input events.
+//!
+//! An `EventRecorder` writes the events an application receives to a file, and an
+//! `EventPlayer` feeds them back into a window through `Window::inject_event`. Recordings are
+//! stored as JSON, one event per line.
+//!
+//! Only available with the `recording` feature.
+//!
+//! # Example
+//!
+//! ~~~ignore
+//! let recorder = glfw::recording::EventRecorder::create("input.jsonl")?;
+//! recorder.record_joystick_events(&mut glfw);
+//! while !window.should_close() {
+//!     glfw.poll_events();
+//!     for (_, event) in recorder.flush_messages(&events) {
+//!         // handle event
+//!     }
+//! }
+//! recorder.finish()?;
+//!
+//! // later, possibly in a test
+//! let mut player = glfw::recording::EventPlayer::open("input.jsonl", Playback::Timed)?;
+//! while !player.is_finished() {
+//!     glfw.poll_events();
+//!     player.play(&mut window);
+//!     for (_, event) in glfw::flush_messages(&events) {
+//!         // handle event
+//!     }
+//! }
+//! ~~~
+
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::path::Path;
+use std::rc::Rc;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    ffi, Glfw, GlfwReceiver, JoystickEvent, JoystickId, MonitorEvent, Window, WindowEvent,
+};
+
+/// An event captured by an `EventRecorder`.
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub enum RecordedEvent {
+    /// An event delivered to a window's receiver.
+    Window(WindowEvent),
+    /// A joystick was connected or disconnected.
+    Joystick(JoystickId, JoystickEvent),
+    /// A monitor was connected or disconnected. Monitors are recorded by name, as the handle is
+    /// not valid beyond the callback.
+    Monitor(Option<String>, MonitorEvent),
+}
+
+/// A `RecordedEvent`, along with the GLFW time at which it occurred.
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct Record {
+    pub time: f64,
+    pub event: RecordedEvent,
+}
+
+#[derive(Debug)]
+struct RecorderState<W> {
+    writer: W,
+    error: Option<io::Error>,
+}
+
+/// Writes events to a recording as they are delivered.
+///
+/// Clones write to the same recording, so one can be moved into a callback while another is
+/// used to flush a window's receiver.
+#[derive(Debug)]
+pub struct EventRecorder<W: Write> {
+    state: Rc<RefCell<RecorderState<W>>>,
+}
+
+impl EventRecorder<BufWriter<File>> {
+    /// Creates a recording at `path`, truncating any existing file.
+    pub fn create<P: AsRef<Path>>(path: P) -> io::Result<EventRecorder<BufWriter<File>>> {
+        Ok(EventRecorder::new(BufWriter::new(File::create(path)?)))
+    }
+}
+
+impl<W: Write> EventRecorder<W> {
+    /// Creates a recorder writing to `writer`.
+    pub fn new(writer: W) -> EventRecorder<W> {
+        EventRecorder {
+            state: Rc::new(RefCell::new(RecorderState {
+                writer,
+                error: None,
+            })),
+        }
+    }
+
+    /// Appends an event to the recording.
+    ///
+    /// Write errors are reported by `finish`. Once one has occurred, further events are
+    /// discarded.
+    pub fn record(&self, time: f64, event: RecordedEvent) {
+        let mut state = self.state.borrow_mut();
+        if state.error.is_some() {
+            return;
+        }
+        let record = Record { time, event };
+        let result = serde_json::to_writer(&mut state.writer, &record)
+            .map_err(io::Error::from)
+            .and_then(|()| state.writer.write_all(b"\n"));
+        if let Err(err) = result {
+            state.error = Some(err);
+        }
+    }
+
+    /// Like `glfw::flush_messages`, but records every event before yielding it.
+    pub fn flush_messages<'a>(
+        &'a self,
+        receiver: &'a GlfwReceiver<(f64, WindowEvent)>,
+    ) -> RecordedMessages<'a, W> {
+        RecordedMessages {
+            recorder: self,
+            receiver,
+        }
+    }
+
+    /// Sets the joystick callback to one recording every joystick event.
+    ///
+    /// This replaces any joystick callback that was previously set.
+    pub fn record_joystick_events(&self, glfw: &mut Glfw)
+    where
+        W: 'static,
+    {
+        let recorder = self.clone();
+        glfw.set_joystick_callback(move |id, event| {
+            recorder.record(now(), RecordedEvent::Joystick(id, event));
+        });
+    }
+
+    /// Sets the monitor callback to one recording every monitor event.
+    ///
+    /// This replaces any monitor callback that was previously set, including the one of a
+    /// `MonitorRegistry`, which then stops receiving monitor events.
+    pub fn record_monitor_events(&self, glfw: &mut Glfw)
+    where
+        W: 'static,
+    {
+        let recorder = self.clone();
+        glfw.set_monitor_callback(move |monitor, event| {
+            recorder.record(now(), RecordedEvent::Monitor(monitor.get_name(), event));
+        });
+    }
+
+    /// Flushes the recording.
+    ///
+    /// # Returns
+    ///
+    /// The first error that occurred while writing the recording, if any.
+    pub fn finish(&self) -> io::Result<()> {
+        let mut state = self.state.borrow_mut();
+        match state.error.take() {
+            Some(err) => Err(err),
+            None => state.writer.flush(),
+        }
+    }
+
+    /// Returns the writer, or `Err(self)` if this recorder has clones left.
+    ///
+    /// Errors that `finish` has not reported yet are discarded.
+    pub fn into_inner(self) -> Result<W, EventRecorder<W>> {
+        Rc::try_unwrap(self.state)
+            .map(|state| state.into_inner().writer)
+            .map_err(|state| EventRecorder { state })
+    }
+}
+
+impl<W: Write> Clone for EventRecorder<W> {
+    fn clone(&self) -> Self {
+        EventRecorder {
+            state: self.state.clone(),
+        }
+    }
+}
+
+/// The GLFW time, for events whose callbacks do not provide one.
+fn now() -> f64 {
+    unsafe { ffi::glfwGetTime() as f64 }
+}
+
+/// An iterator that records and yields the messages in a `GlfwReceiver`'s queue.
+///
+/// Returned by `EventRecorder::flush_messages`.
+#[derive(Debug)]
+pub struct RecordedMessages<'a, W: Write> {
+    recorder: &'a EventRecorder<W>,
+    receiver: &'a GlfwReceiver<(f64, WindowEvent)>,
+}
+
+impl<'a, W: Write> Iterator for RecordedMessages<'a, W> {
+    type Item = (f64, WindowEvent);
+
+    fn next(&mut self) -> Option<(f64, WindowEvent)> {
+        let (time, event) = self.receiver.receive()?;
+        self.recorder
+            .record(time, RecordedEvent::Window(event.clone()));
+        Some((time, event))
+    }
+}
+
+/// How an `EventPlayer` paces a recording.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum Playback {
+    /// Events are played back with the same spacing as they were recorded with.
+    Timed,
+    /// All remaining events are played back by the next call to `EventPlayer::play`.
+    AsFastAsPossible,
+}
+
+/// Plays a recording back into a window.
+#[derive(Debug)]
+pub struct EventPlayer {
+    records: VecDeque<Record>,
+    playback: Playback,
+    /// The GLFW time minus the recorded time, fixed by the first call to `play`.
+    offset: Option<f64>,
+}
+
+impl EventPlayer {
+    /// Creates a player for the recorded events given.
+    pub fn new(records: Vec<Record>, playback: Playback) -> EventPlayer {
+        EventPlayer {
+            records: records.into(),
+            playback,
+            offset: None,
+        }
+    }
+
+    /// Reads a recording made by `EventRecorder::create`.
+    pub fn open<P: AsRef<Path>>(path: P, playback: Playback) -> io::Result<EventPlayer> {
+        EventPlayer::from_reader(BufReader::new(File::open(path)?), playback)
+    }
+
+    /// Reads a recording made by an `EventRecorder`.
+    pub fn from_reader<R: BufRead>(reader: R, playback: Playback) -> io::Result<EventPlayer> {
+        let mut records = Vec::new();
+        for line in reader.lines() {
+            let line = line?;
+            if !line.trim().is_empty() {
+                records.push(serde_json::from_str(&line)?);
+            }
+        }
+        Ok(EventPlayer::new(records, playback))
+    }
+
+    /// Whether every event has been played back.
+    pub fn is_finished(&self) -> bool {
+        self.records.is_empty()
+    }
+
+    /// Plays back the events that are due.
+    ///
+    /// Window events are injected into `window`, and are timestamped with the current time
+    /// rather than the recorded one. Joystick and monitor events cannot be injected, so they
+    /// are returned for the caller to handle instead.
+    ///
+    /// With `Playback::Timed`, the first call plays the first event, and later events are due
+    /// once as much time has passed since then as had passed when they were recorded.
+    pub fn play(&mut self, window: &mut Window) -> Vec<Record> {
+        let mut other = Vec::new();
+        let now = window.glfw.get_time();
+        while let Some(record) = self.records.front() {
+            if self.playback == Playback::Timed {
+                let offset = *self.offset.get_or_insert(now - record.time);
+                if record.time + offset > now {
+                    break;
+                }
+            }
+            let record = self.records.pop_front().unwrap();
+            match record.event {
+                RecordedEvent::Window(event) => window.inject_event(event),
+                _ => other.push(record),
+            }
+        }
+        other
+    }
+}
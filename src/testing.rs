@@ -0,0 +1,170 @@
+// Copyright 2013 The GLFW-RS Developers. For a full listing of the authors,
+// refer to the AUTHORS file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Headless test support, built on the `Null` platform.
+//!
+//! The `Null` platform needs no display server, so code using windows, monitors and event
+//! receivers can be exercised on a CI machine without one. It reports a single virtual monitor
+//! and no joysticks, and window operations such as `set_pos` and `set_size` invoke the window
+//! callbacks immediately. Input the `Null` platform cannot produce, such as key presses, can be
+//! simulated with `Window::inject_event`.
+//!
+//! Only available with the `testing` feature.
+//!
+//! # Example
+//!
+//! ~~~ignore
+//! #[test]
+//! fn resize() {
+//!     let mut glfw = glfw::testing::init().unwrap();
+//!     let (mut window, events) = glfw.create_window(640, 480);
+//!     window.set_size_polling(true);
+//!     window.set_size(800, 600);
+//!     let sizes: Vec<_> = glfw::flush_messages(&events).collect();
+//!     assert!(matches!(sizes[..], [(_, glfw::WindowEvent::Size(800, 600))]));
+//! }
+//! ~~~
+
+use std::ops::{Deref, DerefMut};
+
+use crate::spin_lock::{SpinLock, SpinLockGuard};
+use crate::{
+    ClientApiHint, Glfw, GlfwReceiver, InitError, InitHint, Joystick, JoystickId, Monitor, PWindow,
+    Platform, WindowBuilder, WindowEvent, WindowHint,
+};
+
+/// GLFW is a process wide singleton, but the test harness runs tests on several threads at
+/// once. Every `Headless` instance holds this lock so that tests take turns.
+static LOCK: SpinLock<()> = SpinLock::new(());
+
+const JOYSTICKS: [JoystickId; 16] = [
+    JoystickId::Joystick1,
+    JoystickId::Joystick2,
+    JoystickId::Joystick3,
+    JoystickId::Joystick4,
+    JoystickId::Joystick5,
+    JoystickId::Joystick6,
+    JoystickId::Joystick7,
+    JoystickId::Joystick8,
+    JoystickId::Joystick9,
+    JoystickId::Joystick10,
+    JoystickId::Joystick11,
+    JoystickId::Joystick12,
+    JoystickId::Joystick13,
+    JoystickId::Joystick14,
+    JoystickId::Joystick15,
+    JoystickId::Joystick16,
+];
+
+/// Initializes GLFW on the `Null` platform, with `fail_on_errors` as the error callback.
+///
+/// Blocks until any other `Headless` instance in the process has been dropped. Errors a test
+/// expects to see can be collected with `Glfw::capture_errors` instead of panicking.
+///
+/// # Returns
+///
+/// `Err(InitError::Internal)` if the linked GLFW library was built without the `Null` platform
+/// or failed to initialize.
+pub fn init() -> Result<Headless, InitError> {
+    let lock = LOCK.lock();
+    if !Platform::Null.is_supported() {
+        return Err(InitError::Internal);
+    }
+    crate::init_hint(InitHint::Platform(Platform::Null));
+    let glfw = crate::init(crate::fail_on_errors);
+    // Init hints outlive the library, don't leak this one into later `glfw::init` calls.
+    crate::init_hint(InitHint::Platform(Platform::Any));
+    Ok(Headless {
+        glfw: glfw?,
+        _lock: lock,
+    })
+}
+
+/// Returns a builder for a window that can be created on the `Null` platform.
+///
+/// The window has no client API, as the `Null` platform can only create OpenGL contexts through
+/// `OSMesa`, which is not usually installed.
+pub fn window_builder(width: u32, height: u32) -> WindowBuilder<'static> {
+    WindowBuilder::new(width, height, "glfw-rs test window")
+        .hint(WindowHint::ClientApi(ClientApiHint::NoApi))
+}
+
+/// A `Glfw` token initialized on the `Null` platform.
+///
+/// Dereferences to `Glfw`. Windows created through it should be dropped before it is, so that
+/// the library is terminated before the next test starts.
+#[derive(Debug)]
+pub struct Headless {
+    glfw: Glfw,
+    _lock: SpinLockGuard<'static, ()>,
+}
+
+impl Headless {
+    /// Creates a window with the hints from `window_builder`.
+    ///
+    /// # Panics
+    ///
+    /// If the window could not be created.
+    pub fn create_window(
+        &mut self,
+        width: u32,
+        height: u32,
+    ) -> (PWindow, GlfwReceiver<(f64, WindowEvent)>) {
+        match window_builder(width, height).try_build(&mut self.glfw) {
+            Ok(window) => window,
+            Err(err) => panic!("{}", err),
+        }
+    }
+
+    /// Supplies the virtual monitor of the `Null` platform to the closure provided.
+    ///
+    /// # Panics
+    ///
+    /// If GLFW reports no monitor.
+    pub fn with_monitor<T, F>(&mut self, f: F) -> T
+    where
+        F: FnOnce(&mut Glfw, &mut Monitor) -> T,
+    {
+        self.glfw
+            .with_primary_monitor(|glfw, monitor| match monitor {
+                Some(monitor) => f(glfw, monitor),
+                None => panic!("the Null platform reported no monitor"),
+            })
+    }
+
+    /// Returns a handle for every joystick slot.
+    ///
+    /// The `Null` platform never reports a joystick as present, so these are useful for testing
+    /// how code copes with absent devices.
+    pub fn joysticks(&self) -> Vec<Joystick> {
+        JOYSTICKS
+            .iter()
+            .map(|&id| self.glfw.get_joystick(id))
+            .collect()
+    }
+}
+
+impl Deref for Headless {
+    type Target = Glfw;
+    fn deref(&self) -> &Glfw {
+        &self.glfw
+    }
+}
+
+impl DerefMut for Headless {
+    fn deref_mut(&mut self) -> &mut Glfw {
+        &mut self.glfw
+    }
+}
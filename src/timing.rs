@@ -0,0 +1,283 @@
+// Copyright 2013 The GLFW-RS Developers. For a full listing of the authors,
+// refer to the AUTHORS file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Frame timing and pacing, measured with the GLFW timer.
+//!
+//! Both types work on the raw timer value rather than `Glfw::get_time`, whose `f64` loses
+//! precision as the uptime grows.
+
+use std::collections::VecDeque;
+use std::thread;
+use std::time::Duration;
+
+use crate::Glfw;
+
+/// How much of each new frame time goes into the smoothed frame time.
+const SMOOTHING: f64 = 0.1;
+
+/// How long before a deadline `FrameLimiter` stops sleeping and starts spinning, as sleeps tend
+/// to overshoot by about a scheduler tick.
+const SPIN_MARGIN: Duration = Duration::from_millis(2);
+
+fn ticks_to_duration(ticks: u64, frequency: u64) -> Duration {
+    // GLFW reports a frequency of 0 on errors.
+    if frequency == 0 {
+        return Duration::ZERO;
+    }
+    let nanos = (ticks % frequency) as u128 * 1_000_000_000 / frequency as u128;
+    Duration::new(ticks / frequency, nanos as u32)
+}
+
+fn duration_to_ticks(duration: Duration, frequency: u64) -> u64 {
+    (duration.as_nanos() * frequency as u128 / 1_000_000_000) as u64
+}
+
+/// Measures the time between frames.
+///
+/// # Example
+///
+/// ~~~ignore
+/// let mut clock = glfw::FrameClock::new(&glfw);
+/// while !window.should_close() {
+///     let dt = clock.tick();
+///     update(dt.as_secs_f64());
+///     render();
+///     window.swap_buffers();
+///     glfw.poll_events();
+/// }
+/// println!("{:.1} fps, 99th percentile {:?}", clock.fps(), clock.percentile(99.0));
+/// ~~~
+#[derive(Debug)]
+pub struct FrameClock {
+    glfw: Glfw,
+    frequency: u64,
+    start: u64,
+    last: u64,
+    frames: u64,
+    delta: u64,
+    /// Exponential moving average of the frame time, in seconds.
+    smoothed: Option<f64>,
+    history: VecDeque<u64>,
+    capacity: usize,
+}
+
+impl FrameClock {
+    /// Creates a clock remembering the last 120 frame times.
+    pub fn new(glfw: &Glfw) -> FrameClock {
+        FrameClock::with_capacity(glfw, 120)
+    }
+
+    /// Creates a clock remembering the last `capacity` frame times.
+    ///
+    /// # Panics
+    ///
+    /// If `capacity` is zero.
+    pub fn with_capacity(glfw: &Glfw, capacity: usize) -> FrameClock {
+        assert!(capacity > 0, "frame history capacity must not be zero");
+        let now = glfw.get_timer_value();
+        FrameClock {
+            glfw: glfw.clone(),
+            frequency: glfw.get_timer_frequency(),
+            start: now,
+            last: now,
+            frames: 0,
+            delta: 0,
+            smoothed: None,
+            history: VecDeque::with_capacity(capacity),
+            capacity,
+        }
+    }
+
+    /// Marks the start of a new frame.
+    ///
+    /// # Returns
+    ///
+    /// The time since the previous call, or since the clock was created.
+    pub fn tick(&mut self) -> Duration {
+        let now = self.glfw.get_timer_value();
+        self.delta = now.wrapping_sub(self.last);
+        self.last = now;
+        self.frames += 1;
+
+        if self.history.len() == self.capacity {
+            self.history.pop_front();
+        }
+        self.history.push_back(self.delta);
+
+        let seconds = self.delta as f64 / self.frequency as f64;
+        self.smoothed = Some(match self.smoothed {
+            Some(smoothed) => smoothed + (seconds - smoothed) * SMOOTHING,
+            None => seconds,
+        });
+
+        self.delta()
+    }
+
+    /// The duration of the last frame, as returned by the last call to `tick`.
+    pub fn delta(&self) -> Duration {
+        ticks_to_duration(self.delta, self.frequency)
+    }
+
+    /// The time since the clock was created or reset.
+    pub fn elapsed(&self) -> Duration {
+        let now = self.glfw.get_timer_value();
+        ticks_to_duration(now.wrapping_sub(self.start), self.frequency)
+    }
+
+    /// The number of calls to `tick` since the clock was created or reset.
+    pub fn frame_count(&self) -> u64 {
+        self.frames
+    }
+
+    /// The frame rate, smoothed over the last few frames so that it can be displayed.
+    ///
+    /// Zero before the first call to `tick`.
+    pub fn fps(&self) -> f64 {
+        match self.smoothed {
+            Some(smoothed) if smoothed > 0.0 => 1.0 / smoothed,
+            _ => 0.0,
+        }
+    }
+
+    /// The frame time below which `percentile` percent of the remembered frames fall, e.g. `99.0`
+    /// for the 99th percentile.
+    ///
+    /// Returns `None` before the first call to `tick`.
+    pub fn percentile(&self, percentile: f64) -> Option<Duration> {
+        if self.history.is_empty() {
+            return None;
+        }
+        let mut sorted: Vec<u64> = self.history.iter().copied().collect();
+        sorted.sort_unstable();
+        // Nearest rank, so that the 100th percentile is the slowest frame.
+        let rank = (percentile.clamp(0.0, 100.0) / 100.0 * sorted.len() as f64).ceil() as usize;
+        let index = rank.max(1) - 1;
+        Some(ticks_to_duration(sorted[index], self.frequency))
+    }
+
+    /// The remembered frame times, from oldest to newest.
+    pub fn frame_times(&self) -> impl Iterator<Item = Duration> + '_ {
+        let frequency = self.frequency;
+        self.history
+            .iter()
+            .map(move |&ticks| ticks_to_duration(ticks, frequency))
+    }
+
+    /// Forgets all frame times and restarts the clock.
+    pub fn reset(&mut self) {
+        let now = self.glfw.get_timer_value();
+        self.start = now;
+        self.last = now;
+        self.frames = 0;
+        self.delta = 0;
+        self.smoothed = None;
+        self.history.clear();
+    }
+}
+
+/// Caps the frame rate by waiting until the next frame is due.
+///
+/// Useful with `SwapInterval::None`, when nothing else throttles the render loop. The limiter
+/// sleeps for most of the wait and spins for the rest, so frames start close to their deadline.
+/// When a frame takes longer than the frame period, the schedule restarts from that frame
+/// rather than rushing to catch up.
+///
+/// # Example
+///
+/// ~~~ignore
+/// glfw.set_swap_interval(glfw::SwapInterval::None);
+/// let mut limiter = glfw::FrameLimiter::new(&glfw, 144.0);
+/// while !window.should_close() {
+///     render();
+///     window.swap_buffers();
+///     glfw.poll_events();
+///     limiter.wait();
+/// }
+/// ~~~
+#[derive(Debug)]
+pub struct FrameLimiter {
+    glfw: Glfw,
+    frequency: u64,
+    period: u64,
+    deadline: u64,
+}
+
+impl FrameLimiter {
+    /// Creates a limiter for `target_rate` frames per second.
+    ///
+    /// # Panics
+    ///
+    /// If `target_rate` is not positive and finite.
+    pub fn new(glfw: &Glfw, target_rate: f64) -> FrameLimiter {
+        let frequency = glfw.get_timer_frequency();
+        let mut limiter = FrameLimiter {
+            glfw: glfw.clone(),
+            frequency,
+            period: 0,
+            deadline: 0,
+        };
+        limiter.set_target_rate(target_rate);
+        limiter
+    }
+
+    /// The number of frames per second the limiter aims for.
+    pub fn target_rate(&self) -> f64 {
+        self.frequency as f64 / self.period as f64
+    }
+
+    /// Changes the number of frames per second the limiter aims for, starting from the next
+    /// frame.
+    ///
+    /// # Panics
+    ///
+    /// If `target_rate` is not positive and finite.
+    pub fn set_target_rate(&mut self, target_rate: f64) {
+        assert!(
+            target_rate.is_finite() && target_rate > 0.0,
+            "target frame rate must be positive and finite"
+        );
+        // Tiny rates saturate the period, and with it the deadline, rather than wrapping it.
+        self.period = ((self.frequency as f64 / target_rate) as u64).max(1);
+        self.deadline = self.glfw.get_timer_value().saturating_add(self.period);
+    }
+
+    /// Blocks until the next frame is due.
+    ///
+    /// # Returns
+    ///
+    /// How long the call waited.
+    pub fn wait(&mut self) -> Duration {
+        let start = self.glfw.get_timer_value();
+        if start >= self.deadline {
+            // Late already, start a new schedule from this frame.
+            self.deadline = start.saturating_add(self.period);
+            return Duration::ZERO;
+        }
+
+        let margin = duration_to_ticks(SPIN_MARGIN, self.frequency);
+        let remaining = self.deadline - start;
+        if remaining > margin {
+            thread::sleep(ticks_to_duration(remaining - margin, self.frequency));
+        }
+        let mut now = self.glfw.get_timer_value();
+        while now < self.deadline {
+            std::hint::spin_loop();
+            now = self.glfw.get_timer_value();
+        }
+
+        self.deadline = self.deadline.saturating_add(self.period);
+        ticks_to_duration(now - start, self.frequency)
+    }
+}
@@ -0,0 +1,192 @@
+// Copyright 2013 The GLFW-RS Developers. For a full listing of the authors,
+// refer to the AUTHORS file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Selection of a monitor's video mode by preference.
+
+use std::fmt;
+
+use crate::{Monitor, VidMode};
+
+type Score = Box<dyn Fn(&VidMode) -> i64>;
+
+fn gcd(a: u32, b: u32) -> u32 {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+impl VidMode {
+    /// The aspect ratio of the resolution in lowest terms, e.g. `(16, 9)` for 1920 x 1080.
+    ///
+    /// Some modes, such as 1366 x 768, do not reduce to their nominal ratio. `VideoModeQuery`
+    /// allows for this when matching aspect ratios.
+    pub fn aspect_ratio(&self) -> (u32, u32) {
+        match gcd(self.width, self.height) {
+            0 => (0, 0),
+            divisor => (self.width / divisor, self.height / divisor),
+        }
+    }
+
+    /// The sum of the red, green and blue bit depths.
+    pub fn bit_depth(&self) -> u32 {
+        self.red_bits + self.green_bits + self.blue_bits
+    }
+}
+
+/// Preferences for choosing a video mode, used by `Monitor::best_video_mode`.
+///
+/// Modes that fail a requirement are never chosen. Of the remaining modes, the one with the
+/// highest custom score wins, then the one with the resolution nearest to the requested one (or
+/// the largest, if none was requested), then the one with the refresh rate nearest to the
+/// requested one (or the highest), then the one with the greatest bit depth.
+///
+/// # Example
+///
+/// ~~~ignore
+/// let query = glfw::VideoModeQuery::new()
+///     .resolution(1920, 1080)
+///     .refresh_rate(144)
+///     .bit_depth(8);
+/// let mode = monitor.best_video_mode(&query).unwrap();
+/// ~~~
+#[derive(Default)]
+pub struct VideoModeQuery {
+    resolution: Option<(u32, u32)>,
+    exact_resolution: bool,
+    refresh_rate: Option<u32>,
+    aspect_ratio: Option<(u32, u32)>,
+    bit_depth: Option<u32>,
+    score: Option<Score>,
+}
+
+impl VideoModeQuery {
+    /// Creates a query preferring the largest resolution with the highest refresh rate.
+    pub fn new() -> VideoModeQuery {
+        VideoModeQuery::default()
+    }
+
+    /// Prefers the resolution nearest to `width` x `height`.
+    pub fn resolution(mut self, width: u32, height: u32) -> VideoModeQuery {
+        self.resolution = Some((width, height));
+        self.exact_resolution = false;
+        self
+    }
+
+    /// Requires a resolution of exactly `width` x `height`.
+    pub fn exact_resolution(mut self, width: u32, height: u32) -> VideoModeQuery {
+        self.resolution = Some((width, height));
+        self.exact_resolution = true;
+        self
+    }
+
+    /// Prefers the refresh rate nearest to `refresh_rate`, instead of the highest one.
+    pub fn refresh_rate(mut self, refresh_rate: u32) -> VideoModeQuery {
+        self.refresh_rate = Some(refresh_rate);
+        self
+    }
+
+    /// Requires an aspect ratio within 1% of `width` : `height`.
+    pub fn aspect_ratio(mut self, width: u32, height: u32) -> VideoModeQuery {
+        self.aspect_ratio = Some((width, height));
+        self
+    }
+
+    /// Requires `bits` bits for each of the red, green and blue channels.
+    pub fn bit_depth(mut self, bits: u32) -> VideoModeQuery {
+        self.bit_depth = Some(bits);
+        self
+    }
+
+    /// Ranks modes by `score` before any other preference, higher scores first.
+    pub fn score<F>(mut self, score: F) -> VideoModeQuery
+    where
+        F: Fn(&VidMode) -> i64 + 'static,
+    {
+        self.score = Some(Box::new(score));
+        self
+    }
+
+    /// Whether `mode` meets the requirements of the query.
+    pub fn matches(&self, mode: &VidMode) -> bool {
+        if self.exact_resolution && self.resolution != Some((mode.width, mode.height)) {
+            return false;
+        }
+        if let Some((width, height)) = self.aspect_ratio {
+            let lhs = mode.width as u64 * height as u64;
+            let rhs = mode.height as u64 * width as u64;
+            if lhs.max(rhs) - lhs.min(rhs) > rhs / 100 {
+                return false;
+            }
+        }
+        if let Some(bits) = self.bit_depth {
+            if mode.red_bits != bits || mode.green_bits != bits || mode.blue_bits != bits {
+                return false;
+            }
+        }
+        true
+    }
+
+    /// Returns the mode of `modes` the query prefers, or `None` if none meets its requirements.
+    pub fn select(&self, modes: &[VidMode]) -> Option<VidMode> {
+        modes
+            .iter()
+            .filter(|mode| self.matches(mode))
+            .max_by_key(|mode| self.rank(mode))
+            .copied()
+    }
+
+    /// The preferences in order of importance, higher is better.
+    fn rank(&self, mode: &VidMode) -> (i64, i64, i64, u32) {
+        let score = self.score.as_ref().map_or(0, |score| score(mode));
+        let resolution = match self.resolution {
+            Some((width, height)) => {
+                -(distance(mode.width, width) as i64 + distance(mode.height, height) as i64)
+            }
+            None => mode.width as i64 * mode.height as i64,
+        };
+        let refresh_rate = match self.refresh_rate {
+            Some(refresh_rate) => -(distance(mode.refresh_rate, refresh_rate) as i64),
+            None => mode.refresh_rate as i64,
+        };
+        (score, resolution, refresh_rate, mode.bit_depth())
+    }
+}
+
+fn distance(a: u32, b: u32) -> u32 {
+    a.max(b) - a.min(b)
+}
+
+impl fmt::Debug for VideoModeQuery {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("VideoModeQuery")
+            .field("resolution", &self.resolution)
+            .field("exact_resolution", &self.exact_resolution)
+            .field("refresh_rate", &self.refresh_rate)
+            .field("aspect_ratio", &self.aspect_ratio)
+            .field("bit_depth", &self.bit_depth)
+            .field("score", &self.score.is_some())
+            .finish()
+    }
+}
+
+impl Monitor {
+    /// Returns the video mode of this monitor that `query` prefers, or `None` if none meets
+    /// its requirements.
+    pub fn best_video_mode(&self, query: &VideoModeQuery) -> Option<VidMode> {
+        query.select(&self.get_video_modes())
+    }
+}
@@ -0,0 +1,55 @@
+// Copyright 2013 The GLFW-RS Developers. For a full listing of the authors,
+// refer to the AUTHORS file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Checks `init_allocator` on the `Null` platform. Kept apart from the other headless tests, as
+//! the allocator applies to every initialization in the process.
+
+extern crate glfw;
+
+use glfw::{CountingAllocator, InitError};
+
+static ALLOCATOR: CountingAllocator = CountingAllocator::new();
+static OTHER_ALLOCATOR: CountingAllocator = CountingAllocator::new();
+
+#[test]
+fn glfw_allocations_go_through_the_allocator() {
+    glfw::init_allocator(Some(&ALLOCATOR)).unwrap();
+
+    {
+        let mut glfw = glfw::testing::init().unwrap();
+        assert_eq!(
+            glfw::init_allocator(None),
+            Err(InitError::AlreadyInitialized)
+        );
+
+        let (mut window, _events) = glfw.create_window(640, 480);
+        window.set_title("a title long enough to need an allocation of its own");
+        window.set_size(800, 600);
+        assert!(ALLOCATOR.total_allocations() > 0);
+        assert!(ALLOCATOR.live_bytes() > 0);
+    }
+
+    assert_eq!(ALLOCATOR.live_allocations(), 0);
+    assert_eq!(ALLOCATOR.live_bytes(), 0);
+
+    // Switching allocators between initializations takes effect on the next one.
+    let allocations = ALLOCATOR.total_allocations();
+    glfw::init_allocator(Some(&OTHER_ALLOCATOR)).unwrap();
+    drop(glfw::testing::init().unwrap());
+    assert_eq!(ALLOCATOR.total_allocations(), allocations);
+    assert!(OTHER_ALLOCATOR.total_allocations() > 0);
+    assert_eq!(OTHER_ALLOCATOR.live_allocations(), 0);
+    glfw::init_allocator(None).unwrap();
+}
@@ -0,0 +1,972 @@
+// Copyright 2013 The GLFW-RS Developers. For a full listing of the authors,
+// refer to the AUTHORS file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Integration tests run on the `Null` platform. Requires the `testing` feature:
+//!
+//! ~~~text
+//! cargo test --features testing --test headless
+//! ~~~
+
+extern crate glfw;
+
+use std::cell::{Cell, RefCell};
+use std::path::PathBuf;
+use std::rc::Rc;
+
+use glfw::{Action, Error, Key, Modifiers, WindowEvent};
+
+#[test]
+fn reports_null_platform() {
+    let glfw = glfw::testing::init().unwrap();
+    assert_eq!(glfw.get_platform(), glfw::Platform::Null);
+}
+
+#[test]
+fn window_geometry() {
+    let mut glfw = glfw::testing::init().unwrap();
+    let (mut window, _events) = glfw.create_window(640, 480);
+
+    assert_eq!(window.get_size(), (640, 480));
+    assert_eq!(window.get_framebuffer_size(), (640, 480));
+
+    window.set_size(800, 600);
+    window.set_pos(10, 20);
+    assert_eq!(window.get_size(), (800, 600));
+    assert_eq!(window.get_framebuffer_size(), (800, 600));
+    assert_eq!(window.get_pos(), (10, 20));
+
+    assert!(!window.should_close());
+    window.set_should_close(true);
+    assert!(window.should_close());
+}
+
+#[test]
+fn checked_window() {
+    let mut glfw = glfw::testing::init().unwrap();
+    let (mut window, _events) = glfw.create_window(640, 480);
+
+    assert_eq!(window.checked().set_size(320, 240), Ok(()));
+    assert_eq!(window.checked().get_size(), Ok((320, 240)));
+}
+
+#[test]
+fn window_creation_errors() {
+    let mut glfw = glfw::testing::init().unwrap();
+
+    let (result, errors) = glfw.capture_errors(|glfw| {
+        glfw::testing::window_builder(0, 0)
+            .try_build(glfw)
+            .map(|_| ())
+    });
+    assert_eq!(result.unwrap_err().error, Error::InvalidValue);
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].0, Error::InvalidValue);
+}
+
+#[test]
+fn monitor() {
+    let mut glfw = glfw::testing::init().unwrap();
+
+    glfw.with_monitor(|_, monitor| {
+        assert!(monitor.get_name().is_some());
+
+        let mode = monitor.get_video_mode().unwrap();
+        assert!(mode.width > 0 && mode.height > 0);
+        assert!(monitor.get_video_modes().iter().any(|m| {
+            (m.width, m.height, m.refresh_rate) == (mode.width, mode.height, mode.refresh_rate)
+        }));
+
+        let (_, _, width, height) = monitor.get_workarea();
+        assert!(width > 0 && height > 0);
+    });
+
+    glfw.with_connected_monitors(|_, monitors| assert_eq!(monitors.len(), 1));
+}
+
+#[test]
+fn joysticks_are_absent() {
+    let glfw = glfw::testing::init().unwrap();
+
+    let joysticks = glfw.joysticks();
+    assert_eq!(joysticks.len(), 16);
+    for joystick in &joysticks {
+        assert!(!joystick.is_present());
+        assert!(joystick.get_name().is_none());
+    }
+}
+
+#[test]
+fn user_callbacks_are_dispatched() {
+    let mut glfw = glfw::testing::init().unwrap();
+    let (mut window, _events) = glfw.create_window(640, 480);
+
+    let sizes = Rc::new(RefCell::new(Vec::new()));
+    let seen = sizes.clone();
+    window.set_size_callback(move |window, width, height| {
+        // The window passed to the callback is the one being resized.
+        assert_eq!(window.get_size(), (width, height));
+        seen.borrow_mut().push((width, height));
+    });
+
+    window.set_size(800, 600);
+    window.set_size(1024, 768);
+    assert_eq!(sizes.take(), [(800, 600), (1024, 768)]);
+
+    window.unset_size_callback();
+    window.set_size(640, 480);
+    assert!(sizes.take().is_empty());
+}
+
+#[test]
+fn polled_events_are_flushed() {
+    let mut glfw = glfw::testing::init().unwrap();
+    let (mut window, events) = glfw.create_window(640, 480);
+    window.set_size_polling(true);
+    window.set_pos_polling(true);
+
+    window.set_size(800, 600);
+    window.set_pos(30, 40);
+    glfw.poll_events();
+
+    let flushed: Vec<_> = glfw::flush_messages(&events)
+        .map(|(_, event)| event)
+        .collect();
+    assert!(matches!(
+        flushed[..],
+        [WindowEvent::Size(800, 600), WindowEvent::Pos(30, 40)]
+    ));
+    assert!(glfw::flush_messages(&events).next().is_none());
+
+    window.set_size_polling(false);
+    window.set_size(640, 480);
+    assert!(glfw::flush_messages(&events).next().is_none());
+}
+
+#[test]
+fn callbacks_and_polling_both_receive_events() {
+    let mut glfw = glfw::testing::init().unwrap();
+    let (mut window, events) = glfw.create_window(640, 480);
+
+    let calls = Rc::new(Cell::new(0));
+    let counter = calls.clone();
+    window.set_pos_callback(move |_, _, _| counter.set(counter.get() + 1));
+    window.set_pos_polling(true);
+
+    window.set_pos(5, 5);
+    assert_eq!(calls.get(), 1);
+    assert_eq!(glfw::flush_messages(&events).count(), 1);
+}
+
+#[test]
+fn receiver_keeps_order_past_queue_capacity() {
+    let mut glfw = glfw::testing::init().unwrap();
+    let (mut window, events) = glfw.create_window(640, 480);
+    window.set_pos_polling(true);
+
+    // More events than the receiver queue holds, so the rest overflow into its channel.
+    for i in 1..=1000 {
+        window.set_pos(i, i);
+    }
+
+    let positions: Vec<_> = glfw::flush_messages(&events)
+        .map(|(_, event)| match event {
+            WindowEvent::Pos(x, _) => x,
+            event => panic!("unexpected event {:?}", event),
+        })
+        .collect();
+    assert_eq!(positions, (1..=1000).collect::<Vec<_>>());
+}
+
+#[test]
+fn event_timestamps_are_monotonic() {
+    let mut glfw = glfw::testing::init().unwrap();
+    let (mut window, events) = glfw.create_window(640, 480);
+    window.set_pos_polling(true);
+
+    for i in 1..=10 {
+        window.set_pos(i, i);
+    }
+
+    let times: Vec<f64> = glfw::flush_messages(&events).map(|(t, _)| t).collect();
+    assert_eq!(times.len(), 10);
+    assert!(times.windows(2).all(|pair| pair[0] <= pair[1]));
+}
+
+#[test]
+fn injected_events_reach_callbacks_and_receiver() {
+    let mut glfw = glfw::testing::init().unwrap();
+    let (mut window, events) = glfw.create_window(640, 480);
+
+    let keys = Rc::new(RefCell::new(Vec::new()));
+    let seen = keys.clone();
+    window.set_key_callback(move |_, key, _, action, _| seen.borrow_mut().push((key, action)));
+    window.set_key_polling(true);
+
+    let press = WindowEvent::Key(Key::Escape, 9, Action::Press, Modifiers::Shift);
+    window.inject_event(press.clone());
+    assert_eq!(keys.take(), [(Key::Escape, Action::Press)]);
+
+    let flushed: Vec<_> = glfw::flush_messages(&events).collect();
+    assert_eq!(flushed.len(), 1);
+    assert_eq!(flushed[0].1, press);
+}
+
+#[test]
+fn injected_file_drop() {
+    let mut glfw = glfw::testing::init().unwrap();
+    let (mut window, events) = glfw.create_window(640, 480);
+
+    let dropped = Rc::new(RefCell::new(Vec::new()));
+    let seen = dropped.clone();
+    window.set_drag_and_drop_callback(move |_, paths| seen.borrow_mut().extend(paths));
+    window.set_drag_and_drop_polling(true);
+
+    let paths = vec![PathBuf::from("a.txt"), PathBuf::from("b.txt")];
+    window.inject_event(WindowEvent::FileDrop(paths.clone()));
+    assert_eq!(dropped.take(), paths);
+    assert!(matches!(
+        glfw::flush_messages(&events).next(),
+        Some((_, WindowEvent::FileDrop(ref p))) if *p == paths
+    ));
+}
+
+#[test]
+fn injected_events_respect_polling() {
+    let mut glfw = glfw::testing::init().unwrap();
+    let (mut window, events) = glfw.create_window(640, 480);
+
+    window.inject_event(WindowEvent::Char('a'));
+    assert!(glfw::flush_messages(&events).next().is_none());
+
+    window.set_char_polling(true);
+    window.inject_event(WindowEvent::Char('a'));
+    assert!(matches!(
+        glfw::flush_messages(&events).next(),
+        Some((_, WindowEvent::Char('a')))
+    ));
+}
+
+#[cfg(feature = "recording")]
+#[test]
+fn recorded_events_replay_into_window() {
+    use glfw::recording::{EventPlayer, EventRecorder, Playback, RecordedEvent};
+
+    let mut glfw = glfw::testing::init().unwrap();
+    let (mut window, events) = glfw.create_window(640, 480);
+    window.set_all_polling(true);
+
+    let recorded = vec![
+        WindowEvent::Key(Key::A, 30, Action::Press, Modifiers::Control),
+        WindowEvent::CursorPos(12.5, 40.0),
+        WindowEvent::FileDrop(vec![PathBuf::from("scene.json")]),
+    ];
+    let recorder = EventRecorder::new(Vec::new());
+    for event in &recorded {
+        window.inject_event(event.clone());
+    }
+    let flushed: Vec<_> = recorder
+        .flush_messages(&events)
+        .map(|(_, event)| event)
+        .collect();
+    assert_eq!(flushed, recorded);
+    recorder.record(
+        glfw.get_time(),
+        RecordedEvent::Joystick(glfw::JoystickId::Joystick1, glfw::JoystickEvent::Connected),
+    );
+    recorder.finish().unwrap();
+
+    let bytes = recorder.into_inner().unwrap();
+    let mut player = EventPlayer::from_reader(&bytes[..], Playback::AsFastAsPossible).unwrap();
+    let other = player.play(&mut window);
+    assert!(player.is_finished());
+    assert_eq!(other.len(), 1);
+
+    let replayed: Vec<_> = glfw::flush_messages(&events)
+        .map(|(_, event)| event)
+        .collect();
+    assert_eq!(replayed, recorded);
+}
+
+#[test]
+fn input_state_tracks_injected_input() {
+    let mut glfw = glfw::testing::init().unwrap();
+    let (mut window, events) = glfw.create_window(640, 480);
+    window.set_all_polling(true);
+    let mut input = glfw::InputState::new();
+
+    input.begin_frame();
+    window.inject_event(WindowEvent::Key(
+        Key::W,
+        17,
+        Action::Press,
+        Modifiers::Shift,
+    ));
+    window.inject_event(WindowEvent::CursorPos(10.0, 10.0));
+    window.inject_event(WindowEvent::CursorPos(15.0, 7.0));
+    window.inject_event(WindowEvent::Scroll(0.0, 1.0));
+    window.inject_event(WindowEvent::Scroll(0.0, 2.0));
+    for (time, event) in glfw::flush_messages(&events) {
+        input.handle_event(time, &event);
+    }
+    assert!(input.is_key_down(Key::W));
+    assert!(input.was_key_pressed(Key::W));
+    assert_eq!(input.modifiers(), Modifiers::Shift);
+    assert_eq!(input.cursor_delta(), (5.0, -3.0));
+    assert_eq!(input.scroll(), (0.0, 3.0));
+    assert!(input.key_held_for(Key::W, glfw.get_time()).unwrap() >= 0.0);
+
+    input.begin_frame();
+    assert!(input.is_key_down(Key::W));
+    assert!(!input.was_key_pressed(Key::W));
+    assert_eq!(input.cursor_delta(), (0.0, 0.0));
+
+    window.inject_event(WindowEvent::Key(
+        Key::W,
+        17,
+        Action::Release,
+        Modifiers::empty(),
+    ));
+    for (time, event) in glfw::flush_messages(&events) {
+        input.handle_event(time, &event);
+    }
+    assert!(!input.is_key_down(Key::W));
+    assert!(input.was_key_released(Key::W));
+
+    // Injected events bypass GLFW, so its key state disagrees with a held key.
+    window.inject_event(WindowEvent::Key(
+        Key::E,
+        18,
+        Action::Press,
+        Modifiers::empty(),
+    ));
+    for (time, event) in glfw::flush_messages(&events) {
+        input.handle_event(time, &event);
+    }
+    assert!(input.is_key_down(Key::E));
+    input.resync(&window);
+    assert!(!input.is_key_down(Key::E));
+}
+
+#[test]
+fn proxy_delivers_events_from_other_threads() {
+    let mut glfw = glfw::testing::init().unwrap();
+    let (_window, _events) = glfw.create_window(640, 480);
+    let (proxy, user_events) = glfw.create_proxy::<u32>();
+
+    let workers: Vec<_> = (0..4)
+        .map(|i| {
+            let proxy = proxy.clone();
+            std::thread::spawn(move || {
+                for j in 0..100 {
+                    proxy.send(i * 100 + j);
+                }
+            })
+        })
+        .collect();
+    drop(proxy);
+    for worker in workers {
+        worker.join().unwrap();
+    }
+
+    glfw.wait_events_timeout(0.0);
+    let mut received: Vec<_> = glfw::flush_messages(&user_events).collect();
+    received.sort_unstable();
+    assert_eq!(received, (0..400).collect::<Vec<_>>());
+}
+
+#[test]
+fn proxy_outlives_glfw() {
+    let glfw = glfw::testing::init().unwrap();
+    let (proxy, user_events) = glfw.create_proxy::<u32>();
+    drop(glfw);
+
+    std::thread::spawn(move || proxy.send(7)).join().unwrap();
+    assert_eq!(user_events.receive(), Some(7));
+}
+
+#[cfg(feature = "futures")]
+#[test]
+fn receiver_stream_wakes_on_send() {
+    use std::future::Future;
+    use std::pin::Pin;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::task::{Context, Poll, Wake, Waker};
+
+    struct CountingWaker(AtomicUsize);
+
+    impl Wake for CountingWaker {
+        fn wake(self: Arc<Self>) {
+            self.0.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    let mut glfw = glfw::testing::init().unwrap();
+    let (mut window, mut events) = glfw.create_window(640, 480);
+    window.set_focus_polling(true);
+
+    let wakes = Arc::new(CountingWaker(AtomicUsize::new(0)));
+    let waker = Waker::from(wakes.clone());
+    let mut cx = Context::from_waker(&waker);
+
+    {
+        let mut next = Box::pin(events.next_event());
+        assert!(next.as_mut().poll(&mut cx).is_pending());
+        window.inject_event(WindowEvent::Focus(true));
+        assert_eq!(wakes.0.load(Ordering::SeqCst), 1);
+        assert!(matches!(
+            next.as_mut().poll(&mut cx),
+            Poll::Ready(Some((_, WindowEvent::Focus(true))))
+        ));
+    }
+
+    let mut stream = Pin::new(&mut events);
+    assert!(futures_core::Stream::poll_next(stream.as_mut(), &mut cx).is_pending());
+    drop(window);
+    assert!(matches!(
+        futures_core::Stream::poll_next(stream.as_mut(), &mut cx),
+        Poll::Ready(None)
+    ));
+}
+
+#[test]
+fn cursor_modes_and_standard_cursors() {
+    let mut glfw = glfw::testing::init().unwrap();
+    let (mut window, _events) = glfw.create_window(640, 480);
+
+    window.set_cursor_mode(glfw::CursorMode::Captured);
+    assert_eq!(window.get_cursor_mode(), glfw::CursorMode::Captured);
+
+    for &shape in &[
+        glfw::StandardCursor::ResizeNWSE,
+        glfw::StandardCursor::ResizeNESW,
+        glfw::StandardCursor::ResizeAll,
+        glfw::StandardCursor::NotAllowed,
+        glfw::StandardCursor::Hand,
+    ] {
+        let cursor = glfw::Cursor::try_standard(shape).unwrap();
+        window.set_cursor(Some(cursor));
+    }
+}
+
+#[test]
+fn position_hints_and_title() {
+    let mut glfw = glfw::testing::init().unwrap();
+    let (mut window, _events) = glfw::testing::window_builder(640, 480)
+        .title("first")
+        .hint(glfw::WindowHint::PositionX(Some(100)))
+        .hint(glfw::WindowHint::PositionY(Some(200)))
+        .try_build(&mut glfw)
+        .unwrap();
+
+    assert_eq!(window.get_pos(), (100, 200));
+    assert_eq!(window.get_title(), "first");
+    window.set_title("second");
+    assert_eq!(window.get_title(), "second");
+}
+
+#[cfg(feature = "vulkan")]
+#[test]
+fn vulkan_loader_requires_uninitialized_glfw() {
+    let _glfw = glfw::testing::init().unwrap();
+    assert_eq!(
+        glfw::init_vulkan_loader(None),
+        Err(glfw::InitError::AlreadyInitialized)
+    );
+
+    #[cfg(feature = "ash")]
+    {
+        unsafe extern "system" fn no_proc_addr(
+            _: ash::vk::Instance,
+            _: *const std::os::raw::c_char,
+        ) -> ash::vk::PFN_vkVoidFunction {
+            None
+        }
+
+        let entry = unsafe {
+            ash::Entry::from_static_fn(ash::StaticFn {
+                get_instance_proc_addr: no_proc_addr,
+            })
+        };
+        assert_eq!(
+            glfw::init_vulkan_loader_from_entry(&entry),
+            Err(glfw::InitError::AlreadyInitialized)
+        );
+    }
+}
+
+#[test]
+fn gl_loader_requires_current_context() {
+    use glfw::{Context, GlLoaderError};
+
+    let mut glfw = glfw::testing::init().unwrap();
+    let (window, _events) = glfw.create_window(640, 480);
+
+    assert!(!window.is_current());
+    assert_eq!(
+        window.proc_address("glGetString"),
+        Err(GlLoaderError::NotCurrent)
+    );
+    assert!(matches!(window.gl_loader(), Err(GlLoaderError::NotCurrent)));
+}
+
+#[test]
+fn make_current_scoped_restores_previous_context() {
+    use glfw::{Context, ContextCreationApi, WindowBuilder, WindowHint};
+
+    let mut glfw = glfw::testing::init().unwrap();
+    // The `Null` platform only creates OpenGL contexts through OSMesa.
+    let (window, _) = glfw.capture_errors(|glfw| {
+        WindowBuilder::new(64, 64, "glfw-rs test context")
+            .hint(WindowHint::Visible(false))
+            .hint(WindowHint::ContextCreationApi(ContextCreationApi::OsMesa))
+            .try_build(glfw)
+    });
+    let (mut window, _events) = match window {
+        Ok(window) => window,
+        Err(_) => return,
+    };
+
+    assert!(!window.is_current());
+    let ptr = window.window_ptr();
+    {
+        // The guard borrows the window, so the current context is checked directly.
+        let _guard = window.make_current_scoped();
+        assert_eq!(unsafe { glfw::ffi::glfwGetCurrentContext() }, ptr);
+    }
+    assert!(!window.is_current());
+    assert!(unsafe { glfw::ffi::glfwGetCurrentContext() }.is_null());
+}
+
+#[test]
+fn frame_clock_and_limiter() {
+    use std::time::Duration;
+
+    let glfw = glfw::testing::init().unwrap();
+    let mut clock = glfw::FrameClock::with_capacity(&glfw, 4);
+    let mut limiter = glfw::FrameLimiter::new(&glfw, 200.0);
+
+    assert_eq!(clock.percentile(50.0), None);
+    assert_eq!(clock.fps(), 0.0);
+    for _ in 0..6 {
+        limiter.wait();
+        clock.tick();
+    }
+
+    assert_eq!(clock.frame_count(), 6);
+    assert_eq!(clock.frame_times().count(), 4);
+    let median = clock.percentile(50.0).unwrap();
+    let slowest = clock.percentile(100.0).unwrap();
+    assert!(median <= slowest);
+    assert_eq!(Some(slowest), clock.frame_times().max());
+    // The limiter may run late, but never early.
+    assert!(median >= Duration::from_millis(4));
+    assert!(clock.fps() > 0.0 && clock.fps() <= 250.0);
+    assert!((limiter.target_rate() - 200.0).abs() < 1.0);
+}
+
+#[test]
+#[should_panic(expected = "target frame rate must be positive and finite")]
+fn frame_limiter_rejects_infinite_rate() {
+    let glfw = glfw::testing::init().unwrap();
+    glfw::FrameLimiter::new(&glfw, f64::INFINITY);
+}
+
+#[test]
+fn fixed_timestep_loop() {
+    use glfw::app::FixedTimestepHandler;
+
+    #[derive(Default)]
+    struct Counter {
+        updates: u32,
+        renders: u32,
+        sizes: Vec<(i32, i32)>,
+    }
+
+    impl FixedTimestepHandler for Counter {
+        fn event(&mut self, _: &mut glfw::Window, _: f64, event: WindowEvent) {
+            if let WindowEvent::Size(w, h) = event {
+                self.sizes.push((w, h));
+            }
+        }
+
+        fn update(&mut self, window: &mut glfw::Window, dt: f64) {
+            assert_eq!(dt, 0.001);
+            self.updates += 1;
+            if self.updates == 20 {
+                window.set_should_close(true);
+            }
+        }
+
+        fn render(&mut self, _: &mut glfw::Window, alpha: f64) {
+            assert!((0.0..1.0).contains(&alpha));
+            self.renders += 1;
+        }
+    }
+
+    let mut glfw = glfw::testing::init().unwrap();
+    let (mut window, events) = glfw.create_window(640, 480);
+    window.set_size_polling(true);
+    window.set_size(800, 600);
+
+    let mut counter = Counter::default();
+    glfw::app::run_fixed_timestep(&mut glfw, &mut window, &events, 1000.0, &mut counter);
+
+    // The loop only checks `should_close` between frames.
+    assert!(counter.updates >= 20);
+    assert!(counter.renders >= 1);
+    assert_eq!(counter.sizes, vec![(800, 600)]);
+}
+
+#[test]
+#[should_panic(expected = "update rate must be positive and finite")]
+fn fixed_timestep_rejects_infinite_rate() {
+    struct Nothing;
+
+    impl glfw::app::FixedTimestepHandler for Nothing {
+        fn update(&mut self, _: &mut glfw::Window, _: f64) {}
+        fn render(&mut self, _: &mut glfw::Window, _: f64) {}
+    }
+
+    let mut glfw = glfw::testing::init().unwrap();
+    let (mut window, events) = glfw.create_window(640, 480);
+    glfw::app::run_fixed_timestep(&mut glfw, &mut window, &events, f64::INFINITY, &mut Nothing);
+}
+
+#[test]
+fn run_app_dispatches_per_window() {
+    use glfw::app::{AppContext, ApplicationHandler, ControlFlow};
+    use glfw::WindowId;
+
+    #[derive(Default)]
+    struct App {
+        ids: Vec<WindowId>,
+        sizes: Vec<(WindowId, i32, i32)>,
+        iterations: u32,
+        exited_with: Option<usize>,
+    }
+
+    impl ApplicationHandler for App {
+        fn init(&mut self, app: &mut AppContext) {
+            app.set_control_flow(ControlFlow::Poll);
+            for _ in 0..2 {
+                let id = app
+                    .create_window(glfw::testing::window_builder(640, 480))
+                    .unwrap();
+                self.ids.push(id);
+            }
+        }
+
+        fn window_event(&mut self, app: &mut AppContext, id: WindowId, _: f64, event: WindowEvent) {
+            if let WindowEvent::Size(w, h) = event {
+                self.sizes.push((id, w, h));
+                app.window_mut(id).unwrap().set_should_close(true);
+            }
+        }
+
+        fn about_to_wait(&mut self, app: &mut AppContext) {
+            self.iterations += 1;
+            match self.iterations {
+                1 => app.window_mut(self.ids[0]).unwrap().set_size(100, 100),
+                2 => app.window_mut(self.ids[1]).unwrap().set_size(200, 200),
+                _ => {}
+            }
+        }
+
+        fn exit(&mut self, app: &mut AppContext) {
+            self.exited_with = Some(app.window_ids().len());
+        }
+    }
+
+    let mut glfw = glfw::testing::init().unwrap();
+    let mut app = App::default();
+    glfw.run_app(&mut app);
+
+    assert_eq!(
+        app.sizes,
+        vec![(app.ids[0], 100, 100), (app.ids[1], 200, 200)]
+    );
+    assert_eq!(app.iterations, 2);
+    assert_eq!(app.exited_with, Some(0));
+}
+
+#[test]
+fn video_mode_selection() {
+    use glfw::{VidMode, VideoModeQuery};
+
+    let mode = |width, height, bits, refresh_rate| VidMode {
+        width,
+        height,
+        red_bits: bits,
+        green_bits: bits,
+        blue_bits: bits,
+        refresh_rate,
+    };
+    let modes = [
+        mode(1280, 1024, 8, 60),
+        mode(1366, 768, 8, 60),
+        mode(1920, 1080, 6, 240),
+        mode(1920, 1080, 8, 60),
+        mode(1920, 1080, 8, 144),
+        mode(2560, 1440, 8, 165),
+    ];
+
+    assert_eq!(mode(1366, 768, 8, 60).aspect_ratio(), (683, 384));
+    assert_eq!(mode(1920, 1080, 8, 60).aspect_ratio(), (16, 9));
+    assert!(mode(1920, 1080, 8, 60) < mode(1920, 1080, 8, 144));
+
+    assert_eq!(
+        VideoModeQuery::new().select(&modes),
+        Some(mode(2560, 1440, 8, 165))
+    );
+    assert_eq!(
+        VideoModeQuery::new()
+            .resolution(1900, 1000)
+            .refresh_rate(120)
+            .bit_depth(8)
+            .select(&modes),
+        Some(mode(1920, 1080, 8, 144))
+    );
+    assert_eq!(
+        VideoModeQuery::new()
+            .exact_resolution(1920, 1080)
+            .select(&modes),
+        Some(mode(1920, 1080, 6, 240))
+    );
+    assert_eq!(
+        VideoModeQuery::new()
+            .aspect_ratio(16, 9)
+            .resolution(1280, 720)
+            .select(&modes),
+        Some(mode(1366, 768, 8, 60))
+    );
+    assert_eq!(
+        VideoModeQuery::new()
+            .score(|mode| -(mode.refresh_rate as i64))
+            .select(&modes),
+        Some(mode(1920, 1080, 8, 60))
+    );
+    assert_eq!(
+        VideoModeQuery::new()
+            .exact_resolution(800, 600)
+            .select(&modes),
+        None
+    );
+
+    let mut glfw = glfw::testing::init().unwrap();
+    glfw.with_monitor(|_, monitor| {
+        let current = monitor.get_video_mode().unwrap();
+        let query = VideoModeQuery::new().exact_resolution(current.width, current.height);
+        assert!(monitor.best_video_mode(&query).is_some());
+    });
+}
+
+#[test]
+fn monitor_registry_snapshots() {
+    let mut glfw = glfw::testing::init().unwrap();
+    let registry = glfw::MonitorRegistry::new(&mut glfw);
+
+    let monitors = registry.monitors();
+    assert_eq!(monitors.len(), 1);
+    let primary = registry.primary().unwrap();
+    assert_eq!(primary, monitors[0]);
+    assert!(registry.is_connected(primary.id));
+    assert_eq!(registry.info(primary.id), Ok(primary.clone()));
+
+    let (pos, mode) = registry
+        .with_monitor(primary.id, |monitor| {
+            (monitor.get_pos(), monitor.get_video_mode())
+        })
+        .unwrap();
+    assert_eq!(pos, primary.pos);
+    assert_eq!(mode, primary.video_mode);
+
+    glfw.poll_events();
+    registry.refresh();
+    assert_eq!(registry.info(primary.id), Ok(primary.clone()));
+    assert!(registry.take_events().is_empty());
+
+    // Without its callback, the registry checks the connected monitors itself.
+    glfw.set_monitor_callback(|_, _| {});
+    assert!(registry.with_monitor(primary.id, |_| ()).is_ok());
+    assert_eq!(registry.monitors(), vec![primary]);
+    assert!(registry.take_events().is_empty());
+}
+
+#[test]
+fn desktop_layout_queries() {
+    let mut glfw = glfw::testing::init().unwrap();
+    let registry = glfw::MonitorRegistry::new(&mut glfw);
+    let left = registry.primary().unwrap();
+    let (x, y, width, height) = left.bounds();
+    assert!(width > 0 && height > 0);
+
+    // A second monitor to the right of the virtual one.
+    let mut right = left.clone();
+    right.name = Some("right".to_string());
+    right.pos = (x + width, y);
+    right.workarea = (x + width, y + 10, width, height - 10);
+    let layout = glfw::DesktopLayout::new(vec![left.clone(), right.clone()]);
+
+    assert_eq!(layout.bounds(), Some((x, y, 2 * width, height)));
+    assert_eq!(layout.monitor_at(x, y), Some(&left));
+    assert_eq!(layout.monitor_at(x + width, y), Some(&right));
+    assert_eq!(layout.monitor_at(x - 1, y), None);
+    assert_eq!(layout.nearest_monitor(x - 100, y), Some(&left));
+    assert_eq!(
+        layout.monitor_for_rect((x + width - 10, y, 100, 100)),
+        Some(&right)
+    );
+    assert_eq!(layout.monitor_for_rect((x - 200, y, 100, 100)), None);
+    assert_eq!(
+        layout.clamp_to_workarea((x + 3 * width, y, 100, 2 * height)),
+        (x + 2 * width - 100, y + 10, 100, height - 10)
+    );
+
+    let layout = glfw::DesktopLayout::from_registry(&registry);
+    let (mut window, _events) = glfw.create_window(200, 100);
+    window.set_pos(x + 10, y + 10);
+    assert_eq!(layout.monitor_for_window(&window), Some(&left));
+
+    window.set_pos(x - 5000, y - 5000);
+    assert_eq!(layout.monitor_for_window(&window), None);
+    layout.clamp_window(&mut window);
+    let (wx, wy, _, _) = left.workarea;
+    assert_eq!(window.get_pos(), (wx, wy));
+    assert_eq!(window.get_size(), (200, 100));
+
+    // Extreme positions saturate instead of overflowing.
+    let mut far = left.clone();
+    far.pos = (i32::MAX - 10, i32::MAX - 10);
+    far.workarea = (i32::MAX - 10, i32::MAX - 10, 10, 10);
+    let mut near = left.clone();
+    near.pos = (i32::MIN, i32::MIN);
+    let layout = glfw::DesktopLayout::new(vec![near, far]);
+    assert_eq!(
+        layout.bounds(),
+        Some((i32::MIN, i32::MIN, i32::MAX, i32::MAX))
+    );
+    assert_eq!(
+        layout.clamp_to_workarea((i32::MAX - 5, i32::MAX - 5, i32::MAX, i32::MAX)),
+        (i32::MAX - 10, i32::MAX - 10, 10, 10)
+    );
+}
+
+#[test]
+fn gamma_ramps() {
+    use glfw::{GammaError, GammaRamp};
+
+    let identity = GammaRamp::identity(256);
+    assert_eq!(identity.size(), Some(256));
+    assert_eq!(identity.red[0], 0);
+    assert_eq!(identity.red[255], u16::MAX);
+    assert_eq!(GammaRamp::from_gamma(256, 1.0), identity);
+    assert_eq!(GammaRamp::from_brightness_contrast(256, 0.0, 1.0), identity);
+    assert_eq!(GammaRamp::from_color_temperature(256, 6500.0), identity);
+    assert_eq!(GammaRamp::from_lut(256, &[0, u16::MAX]), identity);
+
+    let warm = GammaRamp::from_color_temperature(256, 3000.0);
+    assert_eq!(warm.red[255], u16::MAX);
+    assert!(warm.blue[255] < warm.green[255]);
+
+    let dark = GammaRamp::from_brightness_contrast(256, -1.0, 1.0);
+    assert_eq!(identity.lerp(&dark, 0.0), Ok(identity.clone()));
+    assert_eq!(identity.lerp(&dark, 1.0), Ok(dark.clone()));
+    assert_eq!(
+        identity.lerp(&GammaRamp::identity(16), 0.5),
+        Err(GammaError::WrongSize {
+            expected: 256,
+            actual: 16
+        })
+    );
+    assert_eq!(
+        GammaRamp::from_channels(vec![0; 4], vec![0; 4], vec![0; 3]),
+        Err(GammaError::MismatchedChannels)
+    );
+
+    let mut glfw = glfw::testing::init().unwrap();
+    glfw.with_monitor(|_, monitor| {
+        let original = monitor.try_get_gamma_ramp().unwrap();
+        let size = original.size().unwrap();
+        {
+            let mut guard = monitor.gamma_guard().unwrap();
+            assert_eq!(guard.original(), &original);
+            let half = original.lerp(&GammaRamp::identity(size), 0.5).unwrap();
+            guard.set_ramp(&half).unwrap();
+            assert_eq!(
+                guard.set_ramp(&GammaRamp::identity(size + 1)),
+                Err(GammaError::WrongSize {
+                    expected: size,
+                    actual: size + 1
+                })
+            );
+        }
+        assert_eq!(monitor.try_get_gamma_ramp(), Ok(original));
+    });
+}
+
+#[test]
+fn window_placement_round_trip() {
+    let mut glfw = glfw::testing::init().unwrap();
+    let (name, workarea) =
+        glfw.with_monitor(|_, monitor| (monitor.get_name(), monitor.get_workarea()));
+    let (mut window, _events) = glfw.create_window(300, 200);
+    window.set_pos(workarea.0 + 40, workarea.1 + 30);
+
+    let saved = window.placement();
+    assert_eq!(saved.pos, (workarea.0 + 40, workarea.1 + 30));
+    assert_eq!(saved.size, (300, 200));
+    assert!(!saved.maximized && !saved.iconified);
+    assert_eq!(saved.fullscreen, None);
+    assert_eq!(saved.monitor, name);
+
+    #[cfg(feature = "recording")]
+    {
+        let json = serde_json::to_string(&saved).unwrap();
+        let loaded: glfw::WindowPlacement = serde_json::from_str(&json).unwrap();
+        assert_eq!(loaded, saved);
+    }
+
+    window.set_pos(workarea.0 + 200, workarea.1 + 100);
+    window.set_size(100, 100);
+    assert!(window.restore_placement(&saved));
+    assert_eq!(window.get_pos(), saved.pos);
+    assert_eq!(window.get_size(), saved.size);
+
+    // The saved monitor is gone, so the window is centered on the primary one instead.
+    let mut elsewhere = saved.clone();
+    elsewhere.monitor = Some("disconnected monitor".to_string());
+    elsewhere.size = (workarea.2 * 2, 100);
+    assert!(!window.restore_placement(&elsewhere));
+    assert_eq!(window.get_size(), (workarea.2, 100));
+    assert_eq!(
+        window.get_pos(),
+        (workarea.0, workarea.1 + (workarea.3 - 100) / 2)
+    );
+
+    let mode = glfw.with_monitor(|_, monitor| monitor.get_video_mode().unwrap());
+    let mut fullscreen = saved.clone();
+    fullscreen.fullscreen = Some(glfw::FullscreenPlacement {
+        monitor: name.clone(),
+        video_mode: mode,
+    });
+    assert!(window.restore_placement(&fullscreen));
+    assert_eq!(window.placement().fullscreen, fullscreen.fullscreen);
+    assert_eq!(window.placement().monitor, name);
+
+    assert!(window.restore_placement(&saved));
+    assert_eq!(window.placement().fullscreen, None);
+    assert_eq!(window.get_pos(), saved.pos);
+}